@@ -36,6 +36,7 @@ DEPENDENCIES=(
     "libgtk-4-dev"
     "libadwaita-1-dev"
     "gettext"
+    "nasm"
 )
 
 MISSING_DEPS=()
@@ -98,7 +99,7 @@ Description: JAIR -  Image Resizer
   * Smart presets for Android, iOS, Web, and Social Media
   * Batch processing support
   * Custom dimensions
-  * PNG and JPEG output formats
+  * PNG, JPEG, WebP and AVIF output formats
   * Modern GNOME/GTK4 interface
   * Multi-language support (English and Spanish)
 EOF
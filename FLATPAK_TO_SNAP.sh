@@ -105,7 +105,7 @@ Description: JAIR - Image Resizer
   * Smart presets for Android, iOS, Web, and Social Media
   * Batch processing support
   * Custom dimensions
-  * PNG and JPEG output formats
+  * PNG, JPEG, WebP and AVIF output formats
   * Modern GNOME/GTK4 interface
   * Multi-language support (English and Spanish)
 EOF
@@ -0,0 +1,51 @@
+// Resample strategies over a large size pack
+// By Leandro Santiago
+//
+// Run with `cargo bench --bench resample`. Only resampling is measured,
+// encoding and writing files cost the same with either strategy.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::time::Duration;
+use image::{DynamicImage, Rgba, RgbaImage};
+use jair::models::ios_sizes::complete_apple_pack;
+use jair::models::{ResampleStrategy, ResizeMode};
+use jair::services::processor::SourceImage;
+use jair::services::resampler::ReferenceResampler;
+
+/// A 4000px master with detail at every scale, so no strategy gets off easy
+fn master() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(4000, 4000, |x, y| {
+        Rgba([(x % 256) as u8, (y % 256) as u8, ((x ^ y) % 256) as u8, 255])
+    }))
+}
+
+fn complete_apple_pack_from_4000px(c: &mut Criterion) {
+    let master = master();
+    let sizes = complete_apple_pack();
+
+    let mut group = c.benchmark_group("complete_apple_pack_4000px");
+    // Direct resampling of the whole pack takes several seconds per run
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(120));
+
+    for strategy in ResampleStrategy::all() {
+        group.bench_function(strategy.id(), |b| {
+            b.iter(|| {
+                let source = match strategy {
+                    ResampleStrategy::Direct => SourceImage::new(master.clone(), &ReferenceResampler),
+                    ResampleStrategy::Progressive => {
+                        SourceImage::progressive(master.clone(), &sizes, ResizeMode::Fit, None, &ReferenceResampler)
+                    }
+                };
+                for size in &sizes {
+                    criterion::black_box(source.resize_to(size, ResizeMode::Fit, None));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, complete_apple_pack_from_4000px);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+// Resize backends over the built-in presets
+// By Leandro Santiago
+//
+// Run with `cargo bench --bench resize_backend`. Each preset is resampled
+// from one master, the way a run without progressive resampling does it.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{DynamicImage, Rgba, RgbaImage};
+use jair::models::platform::platforms;
+use jair::models::{ResizeBackend, ResizeMode};
+use jair::services::processor::SourceImage;
+use jair::services::resampler::resampler;
+
+/// A 2048px master with detail at every scale
+fn master() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(2048, 2048, |x, y| {
+        Rgba([(x % 256) as u8, (y % 256) as u8, ((x ^ y) % 256) as u8, (x / 8 % 256) as u8])
+    }))
+}
+
+fn built_in_presets(c: &mut Criterion) {
+    let master = master();
+
+    let mut group = c.benchmark_group("built_in_presets_2048px");
+    group.sample_size(10);
+
+    for platform in platforms(Vec::new()).iter().filter(|platform| !platform.is_custom()) {
+        for preset in &platform.presets {
+            let sizes = preset.sizes();
+            let id = platform.preset_id(preset.as_ref());
+
+            for backend in ResizeBackend::all() {
+                let source = SourceImage::new(master.clone(), resampler(backend));
+                group.bench_with_input(BenchmarkId::new(backend.id(), &id), &sizes, |b, sizes| {
+                    b.iter(|| {
+                        for size in sizes {
+                            criterion::black_box(source.resize_to(size, ResizeMode::Fit, None));
+                        }
+                    })
+                });
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, built_in_presets);
+criterion_main!(benches);
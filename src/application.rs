@@ -30,6 +30,7 @@ use gtk::{gio, glib};
 use crate::config::VERSION;
 use crate::JairWindow;
 use crate::preferences::JairPreferencesWindow;
+use crate::preset_editor::JairPresetEditor;
 
 mod imp {
     use super::*;
@@ -101,7 +102,10 @@ impl JairApplication {
         let preferences_action = gio::ActionEntry::builder("preferences")
             .activate(move |app: &Self, _, _| app.show_preferences())
             .build();
-        self.add_action_entries([quit_action, about_action, preferences_action]);
+        let presets_action = gio::ActionEntry::builder("presets")
+            .activate(move |app: &Self, _, _| app.show_preset_editor())
+            .build();
+        self.add_action_entries([quit_action, about_action, preferences_action, presets_action]);
     }
 
     fn show_about(&self) {
@@ -130,4 +134,19 @@ impl JairApplication {
         preferences.set_transient_for(Some(&window));
         preferences.present();
     }
+
+    fn show_preset_editor(&self) {
+        let window = self.active_window().unwrap();
+        let editor = JairPresetEditor::new();
+        editor.set_transient_for(Some(&window));
+
+        // Saved presets show up under My Presets right away
+        if let Ok(window) = window.downcast::<JairWindow>() {
+            editor.connect_presets_changed(glib::clone!(@weak window => move |_| {
+                window.reload_user_presets();
+            }));
+        }
+
+        editor.present();
+    }
 }
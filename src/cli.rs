@@ -0,0 +1,687 @@
+/* MIT License
+ *
+ * Copyright (c) 2025 Leandro Santiago
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+use anyhow::Result;
+use gettextrs::{gettext, ngettext};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::models::adaptive_icon::{parse_hex_color, AdaptiveBackground, MonochromeLayer};
+use crate::models::platform::{find_preset, platforms};
+use crate::models::user_preset::UserPreset;
+use crate::models::{
+    merge_sizes, parse_padding, parse_quality, parse_size_list, EncoderSettings, OutputFormat,
+    OutputLayout, PngCompression, PngFilter, ResampleStrategy, ResizeBackend, ResizeMode, Size,
+    UpscalePolicy,
+};
+use crate::services::folder_scan::{self, ScanOptions};
+use crate::services::{processor, user_presets};
+
+/// First argument that switches JAIR into headless batch mode
+pub const COMMAND: &str = "resize";
+
+pub const EXIT_SUCCESS: i32 = 0;
+/// At least one image could not be processed
+pub const EXIT_FAILURE: i32 = 1;
+/// Invalid arguments or nothing to do
+pub const EXIT_USAGE: i32 = 2;
+
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    pub inputs: Vec<PathBuf>,
+    pub sizes: Vec<Size>,
+    pub output: Option<PathBuf>,
+    pub output_format: OutputFormat,
+    pub encoder: EncoderSettings,
+    pub layout: OutputLayout,
+    pub resize_mode: ResizeMode,
+    pub upscale: UpscalePolicy,
+    pub resample: ResampleStrategy,
+    pub resize_backend: ResizeBackend,
+    /// Worker threads, 0 uses one per CPU core
+    pub threads: usize,
+    /// How directories given as inputs are searched
+    pub scan: ScanOptions,
+    pub list_presets: bool,
+    pub help: bool,
+}
+
+/// Run the headless resizer with the arguments following `jair resize`
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("jair: {}", message);
+            eprintln!("{}", gettext("Try 'jair resize --help' for more information."));
+            return EXIT_USAGE;
+        }
+    };
+
+    if options.help {
+        print!("{}", usage());
+        return EXIT_SUCCESS;
+    }
+
+    if options.list_presets {
+        let loaded = user_presets::load_all();
+        report_skipped_presets(&loaded.skipped);
+        for (id, name) in preset_list(loaded.presets) {
+            println!("{:<36} {}", id, name);
+        }
+        return EXIT_SUCCESS;
+    }
+
+    let Some(out_dir) = options.output else {
+        eprintln!("jair: {}", gettext("An output directory is required (--output)"));
+        return EXIT_USAGE;
+    };
+
+    if options.sizes.is_empty() {
+        eprintln!("jair: {}", gettext("Select at least one preset (--preset) or size (--size)"));
+        return EXIT_USAGE;
+    }
+
+    let (images, source_folders) = match collect_images(&options.inputs, &options.scan) {
+        Ok((images, source_folders)) if !images.is_empty() => (images, source_folders),
+        Ok(_) => {
+            eprintln!("jair: {}", gettext("No supported images found in the given inputs"));
+            return EXIT_USAGE;
+        }
+        Err(e) => {
+            eprintln!("jair: {:#}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let process_options = processor::ProcessOptions {
+        output_format: options.output_format,
+        encoder: options.encoder,
+        layout: options.layout,
+        resize_mode: options.resize_mode,
+        upscale: options.upscale,
+        resample: options.resample,
+        resize_backend: options.resize_backend,
+        threads: options.threads,
+        source_folders,
+        ..processor::ProcessOptions::default()
+    };
+
+    let results = processor::batch_resize_and_save_parallel(&images, &out_dir, &options.sizes, &process_options);
+
+    let total = results.len();
+    let mut successful = 0;
+    let mut failed = 0;
+
+    for (input, result) in &results {
+        match result {
+            Ok(report) => {
+                successful += 1;
+                let files = ngettext("{} file", "{} files", report.saved.len() as u32)
+                    .replace("{}", &report.saved.len().to_string());
+                println!("{:<7} {} ({})", gettext("OK"), input.display(), files);
+                if !report.upscaled.is_empty() {
+                    println!("        {} {}", gettext("Enlarged beyond the source:"), report.upscaled.join(", "));
+                }
+                if !report.skipped.is_empty() {
+                    println!("        {} {}", gettext("Skipped, larger than the source:"), report.skipped.join(", "));
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                let failure = processor::ImageFailure::new(input, e);
+                match &failure.size {
+                    Some(size) => println!("{:<7} {} [{}]: {}", gettext("FAILED"), input.display(), size, failure.message),
+                    None => println!("{:<7} {}: {}", gettext("FAILED"), input.display(), failure.message),
+                }
+                if let Some(hint) = &failure.hint {
+                    println!("        {}", hint);
+                }
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        ngettext(
+            "Completed: {successful} successful, {failed} failed out of {total} image",
+            "Completed: {successful} successful, {failed} failed out of {total} images",
+            total as u32,
+        )
+        .replace("{successful}", &successful.to_string())
+        .replace("{failed}", &failed.to_string())
+        .replace("{total}", &total.to_string())
+    );
+
+    if failed > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_SUCCESS
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut options = CliOptions::default();
+
+    let mut resource_name = None;
+    let mut background = None;
+    let mut monochrome = None;
+    let mut padding = None;
+    let mut format_given = false;
+    let mut layout_given = false;
+    let mut preset_format = None;
+    let mut preset_layout = None;
+    // Read from the presets folder the first time a preset is asked for
+    let mut registry = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} {}", gettext("Missing value for"), flag))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "--list-presets" => options.list_presets = true,
+            "-p" | "--preset" => {
+                let id = value(arg)?;
+                let platforms = registry.get_or_insert_with(|| {
+                    let loaded = user_presets::load_all();
+                    report_skipped_presets(&loaded.skipped);
+                    platforms(loaded.presets)
+                });
+                let preset = find_preset(platforms, &id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown preset:"), id))?;
+
+                // The first preset that sets a format or layout provides the default
+                preset_format = preset_format.or(preset.output_format());
+                preset_layout = preset_layout.or(preset.layout());
+                options.sizes.extend(preset.sizes());
+            }
+            "-s" | "--size" => {
+                let list = value(arg)?;
+                let sizes = parse_size_list(&list)
+                    .map_err(|spec| format!("{} {}", gettext("Invalid size:"), spec))?;
+                options.sizes.extend(sizes);
+            }
+            "-o" | "--output" => options.output = Some(PathBuf::from(value(arg)?)),
+            "-f" | "--format" => {
+                let format = value(arg)?;
+                format_given = true;
+                options.output_format = OutputFormat::from_id(&format.to_lowercase())
+                    .ok_or_else(|| format!("{} {}", gettext("Unsupported format:"), format))?;
+            }
+            "-m" | "--mode" => {
+                let id = value(arg)?;
+                options.resize_mode = ResizeMode::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown resize mode:"), id))?;
+            }
+            "--upscale" => {
+                let id = value(arg)?;
+                options.upscale = UpscalePolicy::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown upscale policy:"), id))?;
+            }
+            "--padding" => {
+                let value = value(arg)?;
+                padding = Some(
+                    parse_padding(&value)
+                        .ok_or_else(|| format!("{} {}", gettext("Invalid padding color:"), value))?,
+                );
+            }
+            "--resample" => {
+                let id = value(arg)?;
+                options.resample = ResampleStrategy::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown resample strategy:"), id))?;
+            }
+            "--resize-backend" => {
+                let id = value(arg)?;
+                options.resize_backend = ResizeBackend::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown resize backend:"), id))?;
+            }
+            "-j" | "--threads" => {
+                let threads = value(arg)?;
+                options.threads = threads.parse()
+                    .map_err(|_| format!("{} {}", gettext("Invalid thread count:"), threads))?;
+            }
+            "-r" | "--recursive" => options.scan.recursive = true,
+            "--max-depth" => {
+                let depth = value(arg)?;
+                options.scan.max_depth = Some(
+                    depth.parse()
+                        .map_err(|_| format!("{} {}", gettext("Invalid depth:"), depth))?,
+                );
+            }
+            "--include" | "--exclude" => {
+                let patterns = folder_scan::parse_patterns(&value(arg)?)
+                    .map_err(|pattern| format!("{} {}", gettext("Invalid pattern:"), pattern))?;
+                if arg == "--include" {
+                    options.scan.include.extend(patterns);
+                } else {
+                    options.scan.exclude.extend(patterns);
+                }
+            }
+            "--jpeg-quality" => {
+                let quality = value(arg)?;
+                options.encoder.jpeg_quality = parse_quality(&quality, 1)
+                    .ok_or_else(|| format!("{} {}", gettext("Invalid quality:"), quality))?;
+            }
+            "--progressive" => options.encoder.jpeg_progressive = true,
+            "--png-compression" => {
+                let id = value(arg)?;
+                options.encoder.png_compression = PngCompression::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown PNG compression:"), id))?;
+            }
+            "--png-filter" => {
+                let id = value(arg)?;
+                options.encoder.png_filter = PngFilter::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown PNG filter:"), id))?;
+            }
+            "--webp-quality" => {
+                let quality = value(arg)?;
+                options.encoder.webp_quality = parse_quality(&quality, 0)
+                    .ok_or_else(|| format!("{} {}", gettext("Invalid quality:"), quality))?;
+            }
+            "-l" | "--layout" => {
+                let id = value(arg)?;
+                layout_given = true;
+                options.layout = OutputLayout::from_id(&id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown layout:"), id))?;
+            }
+            "--resource-name" => resource_name = Some(value(arg)?),
+            "--background" => {
+                let value = value(arg)?;
+                background = Some(match parse_hex_color(&value) {
+                    Some(color) => AdaptiveBackground::Color(color),
+                    None => AdaptiveBackground::Image(PathBuf::from(value)),
+                });
+            }
+            "--monochrome" => {
+                let value = value(arg)?;
+                monochrome = Some(match value.as_str() {
+                    "foreground" => MonochromeLayer::FromForeground,
+                    _ => MonochromeLayer::Image(PathBuf::from(value)),
+                });
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("{} {}", gettext("Unknown option:"), flag));
+            }
+            input => options.inputs.push(PathBuf::from(input)),
+        }
+    }
+
+    // Presets often share sizes, e.g. all-launcher-icons and launcher-icons
+    options.sizes = merge_sizes(options.sizes);
+
+    if let (false, Some(format)) = (format_given, preset_format) {
+        options.output_format = format;
+    }
+    if let (false, Some(layout)) = (layout_given, preset_layout) {
+        options.layout = layout;
+    }
+
+    if options.scan.max_depth.is_some() && !options.scan.recursive {
+        return Err(gettext("--max-depth requires --recursive"));
+    }
+
+    if let Some(padding) = padding {
+        let ResizeMode::Contain(color) = &mut options.resize_mode else {
+            return Err(gettext("--padding requires --mode contain"));
+        };
+        *color = padding;
+    }
+
+    if resource_name.is_some() || background.is_some() || monochrome.is_some() {
+        let OutputLayout::AndroidRes { resource_name: name, adaptive } = &mut options.layout else {
+            return Err(gettext(
+                "--resource-name, --background and --monochrome require --layout android-res",
+            ));
+        };
+
+        if let Some(resource_name) = resource_name {
+            *name = resource_name;
+        }
+        if let Some(background) = background {
+            adaptive.background = background;
+        }
+        adaptive.monochrome = monochrome;
+    }
+
+    Ok(options)
+}
+
+/// Preset files that failed to load, so a broken file isn't mistaken for a
+/// missing one
+fn report_skipped_presets(skipped: &[(PathBuf, String)]) {
+    for (path, error) in skipped {
+        eprintln!("jair: {} {}: {}", gettext("Skipped preset"), path.display(), error);
+    }
+}
+
+fn preset_list(saved: Vec<UserPreset>) -> Vec<(String, String)> {
+    let mut presets = Vec::new();
+    for platform in platforms(saved) {
+        for preset in &platform.presets {
+            presets.push((platform.preset_id(preset.as_ref()), preset.name()));
+        }
+    }
+    presets
+}
+
+/// Expand directories and keep supported image files, preserving argument order.
+/// Also returns the subfolder of each image found below an input directory.
+fn collect_images(inputs: &[PathBuf], scan: &ScanOptions) -> Result<(Vec<PathBuf>, HashMap<PathBuf, PathBuf>)> {
+    let mut images = Vec::new();
+    let mut source_folders = HashMap::new();
+    // The same file can be given directly and found again in a folder
+    let mut seen = HashSet::new();
+    let mut add = |path: PathBuf| {
+        if seen.insert(std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone())) {
+            images.push(path);
+            true
+        } else {
+            false
+        }
+    };
+
+    for input in inputs {
+        if input.is_dir() {
+            let scanned = folder_scan::scan_directory(input, scan)?;
+            for (path, error) in &scanned.skipped {
+                eprintln!("jair: {} {}: {}", gettext("Skipped folder"), path.display(), error);
+            }
+            for image in scanned.images {
+                if add(image.path.clone()) && !image.subfolder.as_os_str().is_empty() {
+                    source_folders.insert(image.path, image.subfolder);
+                }
+            }
+        } else if input.is_file() && processor::is_supported_image(input) {
+            add(input.clone());
+        } else {
+            return Err(anyhow::anyhow!("{} {}", gettext("Not a supported image or folder:"), input.display()));
+        }
+    }
+
+    Ok((images, source_folders))
+}
+
+fn usage() -> String {
+    gettext(
+        "Usage: jair resize [OPTIONS] <INPUT>...
+
+Resize images without opening a window. INPUT may be image files or
+directories containing images.
+
+Options:
+  -p, --preset <PLATFORM/PRESET>  Preset to generate, e.g. android/launcher-icons,
+                                  or user/NAME for a preset file in
+                                  $XDG_CONFIG_HOME/jair/presets
+  -s, --size <[NAME=]WxH[:MODE]>  Custom size, e.g. 1920x1080, 1920x, x1080 or
+                                  hero=1024x500:cover. NAME replaces the
+                                  Custom_WxH folder name. Separate several
+                                  sizes with commas
+  -o, --output <DIR>              Output directory
+  -f, --format <FORMAT>           Output format: png (default), jpeg, webp,
+                                  webp-lossless or avif
+  -m, --mode <MODE>               How sizes with both dimensions are filled:
+                                  fit, contain, cover (default) or stretch
+      --padding <COLOR>           Padding for contain: transparent (default)
+                                  or a color such as #FFFFFF
+      --upscale <POLICY>          Sizes larger than the source image: allow,
+                                  warn (default, lists them), skip or fail
+      --resample <STRATEGY>       direct (default) resamples every size from the
+                                  full image, progressive from halved copies
+                                  of it, which is faster for many small sizes
+      --resize-backend <BACKEND>  reference (default) or simd, which resamples
+                                  several channels per instruction
+  -j, --threads <N>               Images and sizes resized at once (default:
+                                  one per CPU core)
+      --jpeg-quality <1-100>      JPEG quality (default: 90)
+      --progressive               Write progressive instead of baseline JPEG
+      --png-compression <LEVEL>   PNG compression: fast (default), default
+                                  or best
+      --png-filter <FILTER>       PNG filter: adaptive (default), none, sub,
+                                  up, avg or paeth
+      --webp-quality <0-100>      Lossy WebP quality (default: 80)
+  -l, --layout <LAYOUT>           Output layout: folders (default), appiconset,
+                                  android-res, ico, iconset or icns
+      --resource-name <NAME>      Android resource name (default: ic_launcher)
+      --background <COLOR|IMAGE>  Adaptive icon background, e.g. #3DDC84 or
+                                  a background image (default: #FFFFFF)
+      --monochrome <IMAGE>        Add a themed icon layer from IMAGE, or from
+                                  the input with 'foreground'
+  -r, --recursive                 Also search subfolders of INPUT directories and
+                                  recreate them under the output directory
+      --max-depth <N>             Subfolder levels to search with --recursive
+      --include <GLOBS>           Only add images matching these comma
+                                  separated patterns, e.g. '*.png,icons/*'
+      --exclude <GLOBS>           Skip images matching these patterns
+      --list-presets              List available presets and exit
+  -h, --help                      Show this help and exit
+
+--preset and --size may be repeated.
+
+Exit status: 0 on success, 1 if any image failed, 2 on invalid usage.
+",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_images, parse_args};
+    use crate::models::android_sizes::all_launcher_icons;
+    use crate::models::generic_sizes::favicons;
+    use crate::models::adaptive_icon::{AdaptiveBackground, MonochromeLayer};
+    use crate::models::{
+        OutputFormat, OutputLayout, PngCompression, PngFilter, ResampleStrategy, ResizeBackend, ResizeMode, Size,
+        UpscalePolicy,
+    };
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn collects_each_image_once() {
+        let dir = std::env::temp_dir().join(format!("jair-collect-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        image::RgbaImage::new(4, 4).save(dir.join("a.png")).unwrap();
+        image::RgbaImage::new(4, 4).save(dir.join("b.png")).unwrap();
+
+        // Given directly, through its folder and again by another spelling of the path
+        let inputs = [dir.join("b.png"), dir.clone(), dir.join(".").join("a.png")];
+        let (mut images, _) = collect_images(&inputs, &Default::default()).unwrap();
+        images.sort();
+        assert_eq!(images, vec![dir.join("a.png"), dir.join("b.png")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parses_presets_sizes_and_inputs() {
+        let options = parse_args(&args(&[
+            "-p", "android/launcher-icons",
+            "--size", "1920x",
+            "-o", "out",
+            "--format", "jpeg",
+            "--layout", "appiconset",
+            "icon.png",
+            "assets",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.sizes.len(), 7);
+        assert_eq!(options.sizes[6].name, "Custom_1920w");
+        assert_eq!(options.output.as_deref(), Some(std::path::Path::new("out")));
+        assert_eq!(options.output_format, OutputFormat::Jpeg);
+        assert_eq!(options.layout, OutputLayout::AppIconSet);
+        assert_eq!(options.inputs.len(), 2);
+    }
+
+    #[test]
+    fn merges_repeated_presets() {
+        let options = parse_args(&args(&[
+            "-p", "android/all-launcher-icons",
+            "-p", "android/launcher-icons",
+            "-p", "web/favicons",
+        ]))
+        .unwrap();
+
+        // The legacy launcher icons are already part of all-launcher-icons
+        let mut expected = all_launcher_icons();
+        expected.extend(favicons());
+        let dimensions = |sizes: &[Size]| -> Vec<(String, u32, u32)> {
+            sizes.iter().map(|size| (size.name.clone(), size.width, size.height)).collect()
+        };
+        assert_eq!(dimensions(&options.sizes), dimensions(&expected));
+    }
+
+    #[test]
+    fn parses_resize_mode_and_padding() {
+        let options = parse_args(&args(&["--padding", "#FFFFFF", "--mode", "contain"])).unwrap();
+        assert_eq!(options.resize_mode, ResizeMode::Contain([0xFF, 0xFF, 0xFF, 0xFF]));
+
+        let options = parse_args(&args(&["-m", "cover", "-s", "512x512:stretch"])).unwrap();
+        assert_eq!(options.resize_mode, ResizeMode::Cover);
+        assert_eq!(options.sizes[0].mode, Some(ResizeMode::Stretch));
+
+        let options = parse_args(&args(&["-s", "1920x1080, thumb=x720", "-s", "1280x"])).unwrap();
+        let names: Vec<&str> = options.sizes.iter().map(|size| size.name.as_str()).collect();
+        assert_eq!(names, ["Custom_1920x1080", "thumb", "Custom_1280w"]);
+        assert!(parse_args(&args(&["-s", "1920x1080, wide"])).is_err());
+
+        assert!(parse_args(&args(&["--mode", "zoom"])).is_err());
+        assert!(parse_args(&args(&["--padding", "#FFFFFF"])).is_err());
+    }
+
+    #[test]
+    fn parses_upscale_policy() {
+        assert_eq!(parse_args(&args(&[])).unwrap().upscale, UpscalePolicy::Warn);
+        assert_eq!(parse_args(&args(&["--upscale", "skip"])).unwrap().upscale, UpscalePolicy::Skip);
+        assert!(parse_args(&args(&["--upscale", "never"])).is_err());
+    }
+
+    #[test]
+    fn parses_resample_strategy() {
+        assert_eq!(parse_args(&args(&[])).unwrap().resample, ResampleStrategy::Direct);
+        let options = parse_args(&args(&["--resample", "progressive"])).unwrap();
+        assert_eq!(options.resample, ResampleStrategy::Progressive);
+        assert!(parse_args(&args(&["--resample", "fast"])).is_err());
+    }
+
+    #[test]
+    fn parses_resize_backend() {
+        assert_eq!(parse_args(&args(&[])).unwrap().resize_backend, ResizeBackend::Reference);
+        let options = parse_args(&args(&["--resize-backend", "simd"])).unwrap();
+        assert_eq!(options.resize_backend, ResizeBackend::Simd);
+        assert!(parse_args(&args(&["--resize-backend", "gpu"])).is_err());
+    }
+
+    #[test]
+    fn parses_thread_count() {
+        assert_eq!(parse_args(&args(&[])).unwrap().threads, 0);
+        assert_eq!(parse_args(&args(&["-j", "4"])).unwrap().threads, 4);
+        assert!(parse_args(&args(&["--threads", "many"])).is_err());
+    }
+
+    #[test]
+    fn parses_folder_scan_options() {
+        let options = parse_args(&args(&[
+            "-r",
+            "--max-depth", "2",
+            "--include", "*.png, icons/*",
+            "--exclude", "draft-*",
+            "assets",
+        ]))
+        .unwrap();
+
+        assert!(options.scan.recursive);
+        assert_eq!(options.scan.max_depth, Some(2));
+        assert_eq!(options.scan.include.len(), 2);
+        assert_eq!(options.scan.exclude[0].as_str(), "draft-*");
+
+        assert!(parse_args(&args(&["--max-depth", "2"])).is_err());
+        assert!(parse_args(&args(&["-r", "--max-depth", "deep"])).is_err());
+        assert!(parse_args(&args(&["--include", "[a"])).is_err());
+    }
+
+    #[test]
+    fn parses_encoder_settings() {
+        let options = parse_args(&args(&[
+            "--jpeg-quality", "75",
+            "--progressive",
+            "--png-compression", "best",
+            "--png-filter", "paeth",
+            "--webp-quality", "60",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.encoder.jpeg_quality, 75);
+        assert!(options.encoder.jpeg_progressive);
+        assert_eq!(options.encoder.png_compression, PngCompression::Best);
+        assert_eq!(options.encoder.png_filter, PngFilter::Paeth);
+        assert_eq!(options.encoder.webp_quality, 60);
+
+        assert!(parse_args(&args(&["--jpeg-quality", "0"])).is_err());
+        assert!(parse_args(&args(&["--webp-quality", "101"])).is_err());
+        assert!(parse_args(&args(&["--png-compression", "max"])).is_err());
+    }
+
+    #[test]
+    fn applies_android_options_to_android_layout() {
+        let options = parse_args(&args(&[
+            "--resource-name", "app_icon",
+            "--background", "#3DDC84",
+            "--monochrome", "foreground",
+            "--layout", "android-res",
+        ]))
+        .unwrap();
+
+        let OutputLayout::AndroidRes { resource_name, adaptive } = options.layout else {
+            panic!("expected the Android resources layout");
+        };
+        assert_eq!(resource_name, "app_icon");
+        assert_eq!(adaptive.background, AdaptiveBackground::Color([0x3D, 0xDC, 0x84]));
+        assert_eq!(adaptive.monochrome, Some(MonochromeLayer::FromForeground));
+    }
+
+    #[test]
+    fn rejects_unknown_presets_formats_and_options() {
+        assert!(parse_args(&args(&["-p", "android/unknown"])).is_err());
+        assert!(parse_args(&args(&["-f", "tga"])).is_err());
+        assert!(parse_args(&args(&["--layout", "xcode"])).is_err());
+        assert!(parse_args(&args(&["--resource-name", "app_icon"])).is_err());
+        assert!(parse_args(&args(&["--background", "#FFFFFF"])).is_err());
+        assert!(parse_args(&args(&["--verbose"])).is_err());
+        assert!(parse_args(&args(&["-o"])).is_err());
+    }
+
+    #[test]
+    fn every_listed_preset_resolves() {
+        for (id, _) in super::preset_list(Vec::new()) {
+            assert!(parse_args(&args(&["-p", &id])).is_ok(), "{}", id);
+        }
+    }
+}
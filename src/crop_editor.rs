@@ -0,0 +1,251 @@
+/* MIT License
+ *
+ * Copyright (c) 2025 Leandro Santiago
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+use gtk::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{cairo, gio, glib};
+use std::path::Path;
+
+use crate::models::CropHint;
+
+/// Drags shorter than this, in pixels, set a focal point instead of a region
+const MIN_DRAG: f64 = 4.0;
+
+mod imp {
+    use super::*;
+
+    use glib::subclass::Signal;
+    use std::cell::Cell;
+    use std::sync::OnceLock;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/codes/lsb/jair/crop_editor.ui")]
+    pub struct JairCropEditor {
+        #[template_child]
+        pub picture: TemplateChild<gtk::Picture>,
+        #[template_child]
+        pub selection_area: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub reset_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub apply_btn: TemplateChild<gtk::Button>,
+
+        // Data
+        pub image_size: Cell<(f64, f64)>,
+        pub crop_hint: Cell<Option<CropHint>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for JairCropEditor {
+        const NAME: &'static str = "JairCropEditor";
+        type Type = super::JairCropEditor;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for JairCropEditor {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.setup_signals();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("applied").build()])
+        }
+    }
+
+    impl WidgetImpl for JairCropEditor {}
+    impl WindowImpl for JairCropEditor {}
+    impl AdwWindowImpl for JairCropEditor {}
+}
+
+glib::wrapper! {
+    pub struct JairCropEditor(ObjectSubclass<imp::JairCropEditor>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JairCropEditor {
+    pub fn new(path: &Path, crop_hint: Option<CropHint>) -> Self {
+        let editor: Self = glib::Object::builder().build();
+        let imp = editor.imp();
+
+        match gtk::gdk::Texture::from_filename(path) {
+            Ok(texture) => {
+                imp.image_size.set((f64::from(texture.width()), f64::from(texture.height())));
+                imp.picture.set_paintable(Some(&texture));
+            }
+            Err(e) => {
+                eprintln!("Could not load {} for cropping: {}", path.display(), e);
+                imp.apply_btn.set_sensitive(false);
+            }
+        }
+
+        imp.crop_hint.set(crop_hint);
+        editor
+    }
+
+    /// The focal point or region chosen by the user, `None` to crop around the centre
+    pub fn crop_hint(&self) -> Option<CropHint> {
+        self.imp().crop_hint.get()
+    }
+
+    /// Called when the user applies the selection; the editor closes itself afterwards
+    pub fn connect_applied<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_local("applied", false, move |values| {
+            let editor = values[0].get::<Self>().expect("applied is emitted by the editor");
+            f(&editor);
+            None
+        })
+    }
+
+    fn setup_signals(&self) {
+        let imp = self.imp();
+
+        imp.selection_area.set_cursor_from_name(Some("crosshair"));
+        imp.selection_area.set_draw_func(glib::clone!(@weak self as editor => move |_, cr, _, _| {
+            editor.draw_selection(cr);
+        }));
+
+        let drag = gtk::GestureDrag::new();
+        drag.connect_drag_update(glib::clone!(@weak self as editor => move |gesture, offset_x, offset_y| {
+            editor.update_selection(gesture, offset_x, offset_y);
+        }));
+        drag.connect_drag_end(glib::clone!(@weak self as editor => move |gesture, offset_x, offset_y| {
+            editor.update_selection(gesture, offset_x, offset_y);
+        }));
+        imp.selection_area.add_controller(drag);
+
+        imp.reset_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+            editor.imp().crop_hint.set(None);
+            editor.imp().selection_area.queue_draw();
+        }));
+
+        imp.apply_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+            editor.emit_by_name::<()>("applied", &[]);
+            editor.close();
+        }));
+    }
+
+    /// Where the picture draws the image inside the selection area: x, y, width, height
+    fn image_rect(&self) -> Option<(f64, f64, f64, f64)> {
+        let imp = self.imp();
+        let (image_width, image_height) = imp.image_size.get();
+        if image_width <= 0.0 || image_height <= 0.0 {
+            return None;
+        }
+
+        let width = f64::from(imp.selection_area.width());
+        let height = f64::from(imp.selection_area.height());
+        let scale = (width / image_width).min(height / image_height);
+        let (shown_width, shown_height) = (image_width * scale, image_height * scale);
+
+        Some((
+            (width - shown_width) / 2.0,
+            (height - shown_height) / 2.0,
+            shown_width,
+            shown_height,
+        ))
+    }
+
+    /// Convert a point in the selection area to fractions of the image
+    fn to_image_point(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let (left, top, width, height) = self.image_rect()?;
+        Some(((x - left) / width, (y - top) / height))
+    }
+
+    fn update_selection(&self, gesture: &gtk::GestureDrag, offset_x: f64, offset_y: f64) {
+        let Some((start_x, start_y)) = gesture.start_point() else {
+            return;
+        };
+        let Some(start) = self.to_image_point(start_x, start_y) else {
+            return;
+        };
+
+        let crop_hint = if offset_x.abs() < MIN_DRAG && offset_y.abs() < MIN_DRAG {
+            CropHint::focal_point(start.0, start.1)
+        } else {
+            let Some(end) = self.to_image_point(start_x + offset_x, start_y + offset_y) else {
+                return;
+            };
+            CropHint::region_from_corners(start, end)
+        };
+
+        self.imp().crop_hint.set(Some(crop_hint));
+        self.imp().selection_area.queue_draw();
+    }
+
+    fn draw_selection(&self, cr: &cairo::Context) {
+        let Some((left, top, width, height)) = self.image_rect() else {
+            return;
+        };
+
+        match self.imp().crop_hint.get() {
+            None => {}
+            Some(CropHint::Region { x, y, width: region_width, height: region_height }) => {
+                let (x, y) = (left + x * width, top + y * height);
+                let (region_width, region_height) = (region_width * width, region_height * height);
+
+                // Dim the parts of the image outside the region
+                cr.set_fill_rule(cairo::FillRule::EvenOdd);
+                cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+                cr.rectangle(left, top, width, height);
+                cr.rectangle(x, y, region_width, region_height);
+                let _ = cr.fill();
+
+                cr.set_source_rgb(1.0, 1.0, 1.0);
+                cr.set_line_width(2.0);
+                cr.rectangle(x, y, region_width, region_height);
+                let _ = cr.stroke();
+            }
+            Some(CropHint::FocalPoint { x, y }) => {
+                let (x, y) = (left + x * width, top + y * height);
+
+                // A dark outline keeps the marker visible on light images
+                for (line_width, shade) in [(4.0, 0.0), (2.0, 1.0)] {
+                    cr.set_line_width(line_width);
+                    cr.set_source_rgb(shade, shade, shade);
+                    cr.new_sub_path();
+                    cr.arc(x, y, 12.0, 0.0, std::f64::consts::TAU);
+                    cr.move_to(x - 20.0, y);
+                    cr.line_to(x + 20.0, y);
+                    cr.move_to(x, y - 20.0);
+                    cr.line_to(x, y + 20.0);
+                    let _ = cr.stroke();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+/* MIT License
+ *
+ * Copyright (c) 2025 Leandro Santiago
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+use gtk::prelude::*;
+use adw::subclass::prelude::*;
+use adw::prelude::*;
+use gtk::{gio, glib};
+use std::path::PathBuf;
+
+use crate::services::folder_scan::{self, ScanOptions};
+
+mod imp {
+    use super::*;
+
+    use glib::subclass::Signal;
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/codes/lsb/jair/folder_import.ui")]
+    pub struct JairFolderImport {
+        #[template_child]
+        pub cancel_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub add_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub folder_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub choose_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub recursive_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub max_depth_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub include_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub exclude_row: TemplateChild<adw::EntryRow>,
+
+        // Data
+        pub folder: RefCell<Option<PathBuf>>,
+        pub scan_options: RefCell<ScanOptions>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for JairFolderImport {
+        const NAME: &'static str = "JairFolderImport";
+        type Type = super::JairFolderImport;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for JairFolderImport {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.setup_signals();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("import").build()])
+        }
+    }
+
+    impl WidgetImpl for JairFolderImport {}
+    impl WindowImpl for JairFolderImport {}
+    impl AdwWindowImpl for JairFolderImport {}
+}
+
+glib::wrapper! {
+    pub struct JairFolderImport(ObjectSubclass<imp::JairFolderImport>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JairFolderImport {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// The chosen folder and how to search it, once the user clicked Add
+    pub fn selection(&self) -> Option<(PathBuf, ScanOptions)> {
+        let imp = self.imp();
+        let folder = imp.folder.borrow().clone()?;
+        Some((folder, imp.scan_options.borrow().clone()))
+    }
+
+    /// Called when the user adds the folder; the dialog closes itself afterwards
+    pub fn connect_import<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_local("import", false, move |values| {
+            let dialog = values[0].get::<Self>().expect("import is emitted by the dialog");
+            f(&dialog);
+            None
+        })
+    }
+
+    fn setup_signals(&self) {
+        let imp = self.imp();
+
+        imp.choose_btn.connect_clicked(glib::clone!(@weak self as dialog => move |_| {
+            dialog.on_choose_clicked();
+        }));
+
+        imp.recursive_row.connect_active_notify(glib::clone!(@weak self as dialog => move |row| {
+            dialog.imp().max_depth_row.set_sensitive(row.is_active());
+        }));
+
+        for row in [&*imp.include_row, &*imp.exclude_row] {
+            row.connect_changed(|row| row.remove_css_class("error"));
+        }
+
+        imp.cancel_btn.connect_clicked(glib::clone!(@weak self as dialog => move |_| {
+            dialog.close();
+        }));
+
+        imp.add_btn.connect_clicked(glib::clone!(@weak self as dialog => move |_| {
+            dialog.on_add_clicked();
+        }));
+    }
+
+    fn on_choose_clicked(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title(gettextrs::gettext("Select Folder"))
+            .modal(true)
+            .build();
+
+        dialog.select_folder(
+            Some(self),
+            gio::Cancellable::NONE,
+            glib::clone!(@weak self as window => move |result| {
+                let Some(folder) = result.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                let imp = window.imp();
+                imp.folder_row.set_subtitle(&glib::markup_escape_text(&folder.display().to_string()));
+                imp.add_btn.set_sensitive(true);
+                imp.folder.replace(Some(folder));
+            }),
+        );
+    }
+
+    fn on_add_clicked(&self) {
+        let imp = self.imp();
+
+        let mut patterns = Vec::new();
+        for row in [&*imp.include_row, &*imp.exclude_row] {
+            match folder_scan::parse_patterns(&row.text()) {
+                Ok(parsed) => patterns.push(parsed),
+                Err(_) => {
+                    row.add_css_class("error");
+                    row.grab_focus();
+                    return;
+                }
+            }
+        }
+        let exclude = patterns.pop().unwrap_or_default();
+        let include = patterns.pop().unwrap_or_default();
+
+        let recursive = imp.recursive_row.is_active();
+        let max_depth = match imp.max_depth_row.value() as usize {
+            0 => None,
+            depth => Some(depth),
+        };
+
+        imp.scan_options.replace(ScanOptions {
+            recursive,
+            max_depth: max_depth.filter(|_| recursive),
+            include,
+            exclude,
+        });
+
+        self.emit_by_name::<()>("import", &[]);
+        self.close();
+    }
+}
@@ -24,11 +24,16 @@
  */
 
 mod application;
+mod cli;
 mod config;
 mod window;
 mod preferences;
-mod models;
-mod services;
+mod crop_editor;
+mod preset_editor;
+mod folder_import;
+mod results_dialog;
+
+use jair::{models, services};
 
 use self::application::JairApplication;
 use self::window::JairWindow;
@@ -92,6 +97,13 @@ fn main() -> glib::ExitCode {
     bind_textdomain_codeset(GETTEXT_PACKAGE, "UTF-8").expect("Failed to set codeset");
     textdomain(GETTEXT_PACKAGE).expect("Failed to set text domain");
 
+    // Headless batch mode (`jair resize ...`) runs without creating a window,
+    // so it also works in build scripts and CI machines without a display
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some(cli::COMMAND) {
+        return glib::ExitCode::from(cli::run(&args[2..]));
+    }
+
     // Load resources - try from multiple locations for development/installed builds
     // First check if JAIR_DATA_DIR environment variable is set (snap environment)
     let mut resource_paths = vec![];
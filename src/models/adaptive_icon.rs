@@ -0,0 +1,68 @@
+// Android Adaptive Icon Layer Definitions
+// By Leandro Santiago
+
+use std::path::PathBuf;
+
+/// Full size of an adaptive icon layer in dp
+pub const LAYER_DP: u32 = 108;
+/// Centre area of the layer that is never masked by the launcher
+pub const SAFE_ZONE_DP: u32 = 66;
+
+/// Background layer of an adaptive icon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdaptiveBackground {
+    /// Written as a `@color` resource in `res/values`
+    Color([u8; 3]),
+    /// Scaled to fill the whole layer
+    Image(PathBuf),
+}
+
+impl Default for AdaptiveBackground {
+    fn default() -> Self {
+        AdaptiveBackground::Color([0xFF, 0xFF, 0xFF])
+    }
+}
+
+/// Source of the `<monochrome>` layer used by Android 13 themed icons.
+/// Only the alpha channel is used, the launcher supplies the tint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonochromeLayer {
+    FromForeground,
+    Image(PathBuf),
+}
+
+/// Extra layers written next to the foreground (the input image)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AdaptiveIconOptions {
+    pub background: AdaptiveBackground,
+    pub monochrome: Option<MonochromeLayer>,
+}
+
+/// Parse `#RRGGBB` or `RRGGBB`
+pub fn parse_hex_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+pub fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_color, parse_hex_color};
+
+    #[test]
+    fn parses_and_formats_hex_colors() {
+        assert_eq!(parse_hex_color("#3DDC84"), Some([0x3D, 0xDC, 0x84]));
+        assert_eq!(parse_hex_color("ffffff"), Some([0xFF, 0xFF, 0xFF]));
+        assert_eq!(parse_hex_color("#FFF"), None);
+        assert_eq!(parse_hex_color("#GGGGGG"), None);
+        assert_eq!(hex_color([0x3D, 0xDC, 0x84]), "#3DDC84");
+    }
+}
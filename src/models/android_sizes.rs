@@ -1,42 +1,44 @@
 // Android PlayStore Image Size Specifications
 // By Leandro Santiago
 
+use super::platform::Preset;
 use super::Size;
 
 /// Android Launcher Icon Sizes (Legacy)
 pub fn launcher_icons() -> Vec<Size> {
     vec![
-        Size { width: 36, height: 36, name: "ldpi".to_string() },
-        Size { width: 48, height: 48, name: "mdpi".to_string() },
-        Size { width: 72, height: 72, name: "hdpi".to_string() },
-        Size { width: 96, height: 96, name: "xhdpi".to_string() },
-        Size { width: 144, height: 144, name: "xxhdpi".to_string() },
-        Size { width: 192, height: 192, name: "xxxhdpi".to_string() },
+        Size::new("ldpi", 36, 36),
+        Size::new("mdpi", 48, 48),
+        Size::new("hdpi", 72, 72),
+        Size::new("xhdpi", 96, 96),
+        Size::new("xxhdpi", 144, 144),
+        Size::new("xxxhdpi", 192, 192),
     ]
 }
 
 /// Android Adaptive Icon Sizes (Modern - requires foreground and background layers)
+/// Each layer is 108dp, so mdpi is 108px and every density scales from there
 pub fn adaptive_icons() -> Vec<Size> {
     vec![
-        Size { width: 81, height: 81, name: "mdpi-adaptive".to_string() },
-        Size { width: 108, height: 108, name: "hdpi-adaptive".to_string() },
-        Size { width: 162, height: 162, name: "xhdpi-adaptive".to_string() },
-        Size { width: 216, height: 216, name: "xxhdpi-adaptive".to_string() },
-        Size { width: 324, height: 324, name: "xxxhdpi-adaptive".to_string() },
+        Size::new("mdpi-adaptive", 108, 108),
+        Size::new("hdpi-adaptive", 162, 162),
+        Size::new("xhdpi-adaptive", 216, 216),
+        Size::new("xxhdpi-adaptive", 324, 324),
+        Size::new("xxxhdpi-adaptive", 432, 432),
     ]
 }
 
 /// Google Play Store Feature Graphic
 pub fn feature_graphic() -> Vec<Size> {
     vec![
-        Size { width: 1024, height: 500, name: "feature-graphic".to_string() },
+        Size::new("feature-graphic", 1024, 500),
     ]
 }
 
 /// Google Play Store Icon (High-res)
 pub fn store_icon() -> Vec<Size> {
     vec![
-        Size { width: 512, height: 512, name: "store-icon".to_string() },
+        Size::new("store-icon", 512, 512),
     ]
 }
 
@@ -45,38 +47,38 @@ pub fn screenshots_phone() -> Vec<Size> {
     vec![
         // Minimum 320px, maximum 3840px
         // Recommended 16:9 aspect ratio
-        Size { width: 1080, height: 1920, name: "phone-portrait".to_string() },
-        Size { width: 1920, height: 1080, name: "phone-landscape".to_string() },
+        Size::new("phone-portrait", 1080, 1920),
+        Size::new("phone-landscape", 1920, 1080),
     ]
 }
 
 /// Google Play Store Screenshots - 7-inch Tablet
 pub fn screenshots_tablet_7() -> Vec<Size> {
     vec![
-        Size { width: 1200, height: 1920, name: "tablet-7-portrait".to_string() },
-        Size { width: 1920, height: 1200, name: "tablet-7-landscape".to_string() },
+        Size::new("tablet-7-portrait", 1200, 1920),
+        Size::new("tablet-7-landscape", 1920, 1200),
     ]
 }
 
 /// Google Play Store Screenshots - 10-inch Tablet
 pub fn screenshots_tablet_10() -> Vec<Size> {
     vec![
-        Size { width: 1600, height: 2560, name: "tablet-10-portrait".to_string() },
-        Size { width: 2560, height: 1600, name: "tablet-10-landscape".to_string() },
+        Size::new("tablet-10-portrait", 1600, 2560),
+        Size::new("tablet-10-landscape", 2560, 1600),
     ]
 }
 
 /// Google Play Store Promo Graphic
 pub fn promo_graphic() -> Vec<Size> {
     vec![
-        Size { width: 180, height: 120, name: "promo-graphic".to_string() },
+        Size::new("promo-graphic", 180, 120),
     ]
 }
 
 /// TV Banner (for Android TV apps)
 pub fn tv_banner() -> Vec<Size> {
     vec![
-        Size { width: 1280, height: 720, name: "tv-banner".to_string() },
+        Size::new("tv-banner", 1280, 720),
     ]
 }
 
@@ -118,6 +120,49 @@ pub fn complete_android_pack() -> Vec<Size> {
     sizes
 }
 
+/// Android project location for a launcher icon size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MipmapEntry {
+    /// Density qualifier, e.g. `xxhdpi` for `mipmap-xxhdpi`
+    pub density: &'static str,
+    /// Adaptive icon layer suffix, e.g. `foreground` for `ic_launcher_foreground.png`
+    pub layer: Option<&'static str>,
+}
+
+impl MipmapEntry {
+    pub fn folder(&self) -> String {
+        format!("mipmap-{}", self.density)
+    }
+
+    pub fn file_stem(&self, resource_name: &str) -> String {
+        match self.layer {
+            Some(layer) => format!("{}_{}", resource_name, layer),
+            None => resource_name.to_string(),
+        }
+    }
+}
+
+/// Look up the `res/mipmap-*` location for one of the launcher icon sizes above.
+/// Store graphics and screenshots are not app resources and have no entry.
+pub fn mipmap_entry(name: &str) -> Option<MipmapEntry> {
+    let (density, layer) = match name.strip_suffix("-adaptive") {
+        Some(density) => (density, Some("foreground")),
+        None => (name, None),
+    };
+
+    let density = match density {
+        "ldpi" => "ldpi",
+        "mdpi" => "mdpi",
+        "hdpi" => "hdpi",
+        "xhdpi" => "xhdpi",
+        "xxhdpi" => "xxhdpi",
+        "xxxhdpi" => "xxxhdpi",
+        _ => return None,
+    };
+
+    Some(MipmapEntry { density, layer })
+}
+
 /// Preset categories for UI selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AndroidPreset {
@@ -173,6 +218,25 @@ impl AndroidPreset {
         }
     }
 
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            AndroidPreset::LauncherIcons => "launcher-icons",
+            AndroidPreset::AdaptiveIcons => "adaptive-icons",
+            AndroidPreset::StoreIcon => "store-icon",
+            AndroidPreset::FeatureGraphic => "feature-graphic",
+            AndroidPreset::ScreenshotsPhone => "screenshots-phone",
+            AndroidPreset::ScreenshotsTablet7 => "screenshots-tablet-7",
+            AndroidPreset::ScreenshotsTablet10 => "screenshots-tablet-10",
+            AndroidPreset::PromoGraphic => "promo-graphic",
+            AndroidPreset::TvBanner => "tv-banner",
+            AndroidPreset::AllPlayStoreGraphics => "all-playstore-graphics",
+            AndroidPreset::AllLauncherIcons => "all-launcher-icons",
+            AndroidPreset::AllScreenshots => "all-screenshots",
+            AndroidPreset::CompleteAndroidPack => "complete-android-pack",
+        }
+    }
+
     pub fn all() -> Vec<AndroidPreset> {
         vec![
             AndroidPreset::CompleteAndroidPack,
@@ -191,3 +255,39 @@ impl AndroidPreset {
         ]
     }
 }
+
+impl Preset for AndroidPreset {
+    fn id(&self) -> &str {
+        AndroidPreset::id(self)
+    }
+
+    fn name(&self) -> String {
+        gettextrs::gettext(AndroidPreset::name(self))
+    }
+
+    fn sizes(&self) -> Vec<Size> {
+        self.get_sizes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all_launcher_icons, mipmap_entry, store_icon};
+
+    #[test]
+    fn launcher_icons_map_to_mipmap_folders() {
+        for size in all_launcher_icons() {
+            assert!(mipmap_entry(&size.name).is_some(), "{}", size.name);
+        }
+
+        let legacy = mipmap_entry("xxhdpi").unwrap();
+        assert_eq!(legacy.folder(), "mipmap-xxhdpi");
+        assert_eq!(legacy.file_stem("ic_launcher"), "ic_launcher");
+
+        let adaptive = mipmap_entry("xxxhdpi-adaptive").unwrap();
+        assert_eq!(adaptive.folder(), "mipmap-xxxhdpi");
+        assert_eq!(adaptive.file_stem("app_icon"), "app_icon_foreground");
+
+        assert!(mipmap_entry(&store_icon()[0].name).is_none());
+    }
+}
@@ -0,0 +1,52 @@
+// Crop Hint Definitions
+// By Leandro Santiago
+
+/// What to keep when an image is cropped to a different aspect ratio.
+/// Coordinates are fractions of the source image, from 0.0 to 1.0,
+/// so a hint stays valid whatever the image is scaled to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CropHint {
+    /// Centre the crop on this point, as far as the image edges allow
+    FocalPoint { x: f64, y: f64 },
+    /// Keep this area; the crop grows around it to match the target ratio
+    Region { x: f64, y: f64, width: f64, height: f64 },
+}
+
+impl CropHint {
+    /// Build a region from two corners in any order
+    pub fn region_from_corners((x1, y1): (f64, f64), (x2, y2): (f64, f64)) -> CropHint {
+        let (left, right) = (x1.min(x2).clamp(0.0, 1.0), x1.max(x2).clamp(0.0, 1.0));
+        let (top, bottom) = (y1.min(y2).clamp(0.0, 1.0), y1.max(y2).clamp(0.0, 1.0));
+
+        CropHint::Region {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+
+    pub fn focal_point(x: f64, y: f64) -> CropHint {
+        CropHint::FocalPoint {
+            x: x.clamp(0.0, 1.0),
+            y: y.clamp(0.0, 1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CropHint;
+
+    #[test]
+    fn normalizes_corners_and_points() {
+        assert_eq!(
+            CropHint::region_from_corners((0.75, 1.5), (0.25, 0.5)),
+            CropHint::Region { x: 0.25, y: 0.5, width: 0.5, height: 0.5 }
+        );
+        assert_eq!(
+            CropHint::focal_point(-0.5, 0.25),
+            CropHint::FocalPoint { x: 0.0, y: 0.25 }
+        );
+    }
+}
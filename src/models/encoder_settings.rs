@@ -0,0 +1,147 @@
+// Encoder Settings Definitions
+// By Leandro Santiago
+
+use super::N_;
+
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+pub const DEFAULT_WEBP_QUALITY: u8 = 80;
+
+/// Quality and compression knobs passed to the image encoders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderSettings {
+    /// 1-100
+    pub jpeg_quality: u8,
+    pub jpeg_progressive: bool,
+    pub png_compression: PngCompression,
+    pub png_filter: PngFilter,
+    /// 0-100, only used for lossy WebP
+    pub webp_quality: u8,
+}
+
+impl Default for EncoderSettings {
+    fn default() -> Self {
+        EncoderSettings {
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
+            jpeg_progressive: false,
+            png_compression: PngCompression::default(),
+            png_filter: PngFilter::default(),
+            webp_quality: DEFAULT_WEBP_QUALITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PngCompression {
+    #[default]
+    Fast,
+    Default,
+    Best,
+}
+
+impl PngCompression {
+    pub fn name(&self) -> &str {
+        match self {
+            PngCompression::Fast => N_("Fast"),
+            PngCompression::Default => N_("Balanced"),
+            PngCompression::Best => N_("Smallest"),
+        }
+    }
+
+    /// Stable identifier used on the command line and in GSettings
+    pub fn id(&self) -> &str {
+        match self {
+            PngCompression::Fast => "fast",
+            PngCompression::Default => "default",
+            PngCompression::Best => "best",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<PngCompression> {
+        Self::all().into_iter().find(|compression| compression.id() == id)
+    }
+
+    pub fn all() -> Vec<PngCompression> {
+        vec![PngCompression::Fast, PngCompression::Default, PngCompression::Best]
+    }
+}
+
+/// Per-scanline PNG filter; `Adaptive` picks the best filter for each line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PngFilter {
+    NoFilter,
+    Sub,
+    Up,
+    Avg,
+    Paeth,
+    #[default]
+    Adaptive,
+}
+
+impl PngFilter {
+    pub fn name(&self) -> &str {
+        match self {
+            PngFilter::NoFilter => N_("None"),
+            PngFilter::Sub => N_("Sub"),
+            PngFilter::Up => N_("Up"),
+            PngFilter::Avg => N_("Average"),
+            PngFilter::Paeth => N_("Paeth"),
+            PngFilter::Adaptive => N_("Adaptive"),
+        }
+    }
+
+    /// Stable identifier used on the command line and in GSettings
+    pub fn id(&self) -> &str {
+        match self {
+            PngFilter::NoFilter => "none",
+            PngFilter::Sub => "sub",
+            PngFilter::Up => "up",
+            PngFilter::Avg => "avg",
+            PngFilter::Paeth => "paeth",
+            PngFilter::Adaptive => "adaptive",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<PngFilter> {
+        Self::all().into_iter().find(|filter| filter.id() == id)
+    }
+
+    pub fn all() -> Vec<PngFilter> {
+        vec![
+            PngFilter::NoFilter,
+            PngFilter::Sub,
+            PngFilter::Up,
+            PngFilter::Avg,
+            PngFilter::Paeth,
+            PngFilter::Adaptive,
+        ]
+    }
+}
+
+/// Parse a quality value, accepting only `min..=100`
+pub fn parse_quality(text: &str, min: u8) -> Option<u8> {
+    text.trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|quality| (min..=100).contains(quality))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_quality, PngCompression, PngFilter};
+
+    #[test]
+    fn ids_round_trip_and_quality_is_bounded() {
+        for compression in PngCompression::all() {
+            assert_eq!(PngCompression::from_id(compression.id()), Some(compression));
+        }
+        for filter in PngFilter::all() {
+            assert_eq!(PngFilter::from_id(filter.id()), Some(filter));
+        }
+
+        assert_eq!(parse_quality(" 75 ", 1), Some(75));
+        assert_eq!(parse_quality("0", 0), Some(0));
+        assert!(parse_quality("0", 1).is_none());
+        assert!(parse_quality("101", 1).is_none());
+        assert!(parse_quality("high", 1).is_none());
+    }
+}
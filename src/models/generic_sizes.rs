@@ -1,26 +1,27 @@
 // Generic Web & Social Media Image Size Specifications
 // By Leandro Santiago
 
+use super::platform::Preset;
 use super::Size;
 
 /// Social Media Profile Pictures
 pub fn social_profile_pictures() -> Vec<Size> {
     vec![
-        Size { width: 180, height: 180, name: "facebook-profile".to_string() },
-        Size { width: 400, height: 400, name: "twitter-profile".to_string() },
-        Size { width: 320, height: 320, name: "instagram-profile".to_string() },
-        Size { width: 300, height: 300, name: "linkedin-profile".to_string() },
-        Size { width: 800, height: 800, name: "youtube-profile".to_string() },
+        Size::new("facebook-profile", 180, 180),
+        Size::new("twitter-profile", 400, 400),
+        Size::new("instagram-profile", 320, 320),
+        Size::new("linkedin-profile", 300, 300),
+        Size::new("youtube-profile", 800, 800),
     ]
 }
 
 /// Social Media Cover/Banner Images
 pub fn social_covers() -> Vec<Size> {
     vec![
-        Size { width: 820, height: 312, name: "facebook-cover".to_string() },
-        Size { width: 1500, height: 500, name: "twitter-header".to_string() },
-        Size { width: 1584, height: 396, name: "linkedin-cover".to_string() },
-        Size { width: 2560, height: 1440, name: "youtube-banner".to_string() },
+        Size::new("facebook-cover", 820, 312),
+        Size::new("twitter-header", 1500, 500),
+        Size::new("linkedin-cover", 1584, 396),
+        Size::new("youtube-banner", 2560, 1440),
     ]
 }
 
@@ -28,97 +29,97 @@ pub fn social_covers() -> Vec<Size> {
 pub fn social_posts() -> Vec<Size> {
     vec![
         // Instagram
-        Size { width: 1080, height: 1080, name: "instagram-square".to_string() },
-        Size { width: 1080, height: 1350, name: "instagram-portrait".to_string() },
-        Size { width: 1080, height: 608, name: "instagram-landscape".to_string() },
-        Size { width: 1080, height: 1920, name: "instagram-story".to_string() },
+        Size::new("instagram-square", 1080, 1080),
+        Size::new("instagram-portrait", 1080, 1350),
+        Size::new("instagram-landscape", 1080, 608),
+        Size::new("instagram-story", 1080, 1920),
 
         // Facebook
-        Size { width: 1200, height: 630, name: "facebook-post".to_string() },
-        Size { width: 1080, height: 1920, name: "facebook-story".to_string() },
+        Size::new("facebook-post", 1200, 630),
+        Size::new("facebook-story", 1080, 1920),
 
         // Twitter
-        Size { width: 1200, height: 675, name: "twitter-post".to_string() },
+        Size::new("twitter-post", 1200, 675),
 
         // LinkedIn
-        Size { width: 1200, height: 627, name: "linkedin-post".to_string() },
+        Size::new("linkedin-post", 1200, 627),
 
         // Pinterest
-        Size { width: 1000, height: 1500, name: "pinterest-pin".to_string() },
+        Size::new("pinterest-pin", 1000, 1500),
 
         // TikTok
-        Size { width: 1080, height: 1920, name: "tiktok-video".to_string() },
+        Size::new("tiktok-video", 1080, 1920),
     ]
 }
 
 /// Web Favicons
 pub fn favicons() -> Vec<Size> {
     vec![
-        Size { width: 16, height: 16, name: "favicon-16".to_string() },
-        Size { width: 32, height: 32, name: "favicon-32".to_string() },
-        Size { width: 48, height: 48, name: "favicon-48".to_string() },
-        Size { width: 64, height: 64, name: "favicon-64".to_string() },
-        Size { width: 128, height: 128, name: "favicon-128".to_string() },
-        Size { width: 256, height: 256, name: "favicon-256".to_string() },
+        Size::new("favicon-16", 16, 16),
+        Size::new("favicon-32", 32, 32),
+        Size::new("favicon-48", 48, 48),
+        Size::new("favicon-64", 64, 64),
+        Size::new("favicon-128", 128, 128),
+        Size::new("favicon-256", 256, 256),
     ]
 }
 
 /// Web Open Graph / Social Share Images
 pub fn og_images() -> Vec<Size> {
     vec![
-        Size { width: 1200, height: 630, name: "og-facebook".to_string() },
-        Size { width: 1200, height: 627, name: "og-linkedin".to_string() },
-        Size { width: 1200, height: 675, name: "og-twitter".to_string() },
-        Size { width: 1200, height: 630, name: "og-general".to_string() },
+        Size::new("og-facebook", 1200, 630),
+        Size::new("og-linkedin", 1200, 627),
+        Size::new("og-twitter", 1200, 675),
+        Size::new("og-general", 1200, 630),
     ]
 }
 
 /// Common Web Thumbnail Sizes
 pub fn web_thumbnails() -> Vec<Size> {
     vec![
-        Size { width: 150, height: 150, name: "thumb-150".to_string() },
-        Size { width: 300, height: 300, name: "thumb-300".to_string() },
-        Size { width: 400, height: 400, name: "thumb-400".to_string() },
-        Size { width: 600, height: 600, name: "thumb-600".to_string() },
+        Size::new("thumb-150", 150, 150),
+        Size::new("thumb-300", 300, 300),
+        Size::new("thumb-400", 400, 400),
+        Size::new("thumb-600", 600, 600),
     ]
 }
 
 /// Common HD Resolutions
 pub fn hd_resolutions() -> Vec<Size> {
     vec![
-        Size { width: 1280, height: 720, name: "hd-720p".to_string() },
-        Size { width: 1920, height: 1080, name: "full-hd-1080p".to_string() },
-        Size { width: 2560, height: 1440, name: "qhd-1440p".to_string() },
-        Size { width: 3840, height: 2160, name: "4k-uhd".to_string() },
+        Size::new("hd-720p", 1280, 720),
+        Size::new("full-hd-1080p", 1920, 1080),
+        Size::new("qhd-1440p", 2560, 1440),
+        Size::new("4k-uhd", 3840, 2160),
     ]
 }
 
 /// Email Newsletter Images
 pub fn email_newsletter() -> Vec<Size> {
     vec![
-        Size { width: 600, height: 400, name: "email-header".to_string() },
-        Size { width: 600, height: 200, name: "email-banner".to_string() },
-        Size { width: 300, height: 300, name: "email-thumbnail".to_string() },
+        Size::new("email-header", 600, 400),
+        Size::new("email-banner", 600, 200),
+        Size::new("email-thumbnail", 300, 300),
     ]
 }
 
 /// Blog Post Images
 pub fn blog_images() -> Vec<Size> {
     vec![
-        Size { width: 1200, height: 630, name: "blog-featured".to_string() },
-        Size { width: 800, height: 600, name: "blog-inline".to_string() },
-        Size { width: 400, height: 300, name: "blog-thumbnail".to_string() },
+        Size::new("blog-featured", 1200, 630),
+        Size::new("blog-inline", 800, 600),
+        Size::new("blog-thumbnail", 400, 300),
     ]
 }
 
 /// E-commerce Product Images
 pub fn ecommerce_products() -> Vec<Size> {
     vec![
-        Size { width: 2000, height: 2000, name: "product-zoom".to_string() },
-        Size { width: 1000, height: 1000, name: "product-large".to_string() },
-        Size { width: 500, height: 500, name: "product-medium".to_string() },
-        Size { width: 250, height: 250, name: "product-thumbnail".to_string() },
-        Size { width: 100, height: 100, name: "product-mini".to_string() },
+        Size::new("product-zoom", 2000, 2000),
+        Size::new("product-large", 1000, 1000),
+        Size::new("product-medium", 500, 500),
+        Size::new("product-thumbnail", 250, 250),
+        Size::new("product-mini", 100, 100),
     ]
 }
 
@@ -193,6 +194,24 @@ impl GenericPreset {
         }
     }
 
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            GenericPreset::SocialProfilePictures => "social-profile-pictures",
+            GenericPreset::SocialCovers => "social-covers",
+            GenericPreset::SocialPosts => "social-posts",
+            GenericPreset::Favicons => "favicons",
+            GenericPreset::OGImages => "og-images",
+            GenericPreset::WebThumbnails => "web-thumbnails",
+            GenericPreset::HDResolutions => "hd-resolutions",
+            GenericPreset::EmailNewsletter => "email-newsletter",
+            GenericPreset::BlogImages => "blog-images",
+            GenericPreset::EcommerceProducts => "ecommerce-products",
+            GenericPreset::CompleteSocialPack => "complete-social-pack",
+            GenericPreset::CompleteWebPack => "complete-web-pack",
+        }
+    }
+
     pub fn all() -> Vec<GenericPreset> {
         vec![
             GenericPreset::CompleteSocialPack,
@@ -210,3 +229,17 @@ impl GenericPreset {
         ]
     }
 }
+
+impl Preset for GenericPreset {
+    fn id(&self) -> &str {
+        GenericPreset::id(self)
+    }
+
+    fn name(&self) -> String {
+        gettextrs::gettext(GenericPreset::name(self))
+    }
+
+    fn sizes(&self) -> Vec<Size> {
+        self.get_sizes()
+    }
+}
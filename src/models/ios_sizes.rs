@@ -1,24 +1,25 @@
 // iOS App Store Image Size Specifications
 // By Leandro Santiago
 
+use super::platform::Preset;
 use super::Size;
 
 /// iOS App Icons (Universal)
 pub fn app_icons() -> Vec<Size> {
     vec![
         // iPhone
-        Size { width: 120, height: 120, name: "iphone-60pt-2x".to_string() },
-        Size { width: 180, height: 180, name: "iphone-60pt-3x".to_string() },
+        Size::new("iphone-60pt-2x", 120, 120),
+        Size::new("iphone-60pt-3x", 180, 180),
 
         // iPad
-        Size { width: 76, height: 76, name: "ipad-76pt-1x".to_string() },
-        Size { width: 152, height: 152, name: "ipad-76pt-2x".to_string() },
+        Size::new("ipad-76pt-1x", 76, 76),
+        Size::new("ipad-76pt-2x", 152, 152),
 
         // iPad Pro
-        Size { width: 167, height: 167, name: "ipad-pro-83.5pt-2x".to_string() },
+        Size::new("ipad-pro-83.5pt-2x", 167, 167),
 
         // App Store
-        Size { width: 1024, height: 1024, name: "app-store-1024pt".to_string() },
+        Size::new("app-store-1024pt", 1024, 1024),
     ]
 }
 
@@ -26,20 +27,20 @@ pub fn app_icons() -> Vec<Size> {
 pub fn spotlight_settings_icons() -> Vec<Size> {
     vec![
         // Spotlight iPhone
-        Size { width: 80, height: 80, name: "spotlight-iphone-40pt-2x".to_string() },
-        Size { width: 120, height: 120, name: "spotlight-iphone-40pt-3x".to_string() },
+        Size::new("spotlight-iphone-40pt-2x", 80, 80),
+        Size::new("spotlight-iphone-40pt-3x", 120, 120),
 
         // Spotlight iPad
-        Size { width: 40, height: 40, name: "spotlight-ipad-40pt-1x".to_string() },
-        Size { width: 80, height: 80, name: "spotlight-ipad-40pt-2x".to_string() },
+        Size::new("spotlight-ipad-40pt-1x", 40, 40),
+        Size::new("spotlight-ipad-40pt-2x", 80, 80),
 
         // Settings iPhone
-        Size { width: 58, height: 58, name: "settings-iphone-29pt-2x".to_string() },
-        Size { width: 87, height: 87, name: "settings-iphone-29pt-3x".to_string() },
+        Size::new("settings-iphone-29pt-2x", 58, 58),
+        Size::new("settings-iphone-29pt-3x", 87, 87),
 
         // Settings iPad
-        Size { width: 29, height: 29, name: "settings-ipad-29pt-1x".to_string() },
-        Size { width: 58, height: 58, name: "settings-ipad-29pt-2x".to_string() },
+        Size::new("settings-ipad-29pt-1x", 29, 29),
+        Size::new("settings-ipad-29pt-2x", 58, 58),
     ]
 }
 
@@ -47,12 +48,12 @@ pub fn spotlight_settings_icons() -> Vec<Size> {
 pub fn notification_icons() -> Vec<Size> {
     vec![
         // iPhone
-        Size { width: 40, height: 40, name: "notification-iphone-20pt-2x".to_string() },
-        Size { width: 60, height: 60, name: "notification-iphone-20pt-3x".to_string() },
+        Size::new("notification-iphone-20pt-2x", 40, 40),
+        Size::new("notification-iphone-20pt-3x", 60, 60),
 
         // iPad
-        Size { width: 20, height: 20, name: "notification-ipad-20pt-1x".to_string() },
-        Size { width: 40, height: 40, name: "notification-ipad-20pt-2x".to_string() },
+        Size::new("notification-ipad-20pt-1x", 20, 20),
+        Size::new("notification-ipad-20pt-2x", 40, 40),
     ]
 }
 
@@ -60,16 +61,16 @@ pub fn notification_icons() -> Vec<Size> {
 pub fn screenshots_iphone() -> Vec<Size> {
     vec![
         // iPhone 15 Pro Max / 14 Pro Max / 13 Pro Max / 12 Pro Max
-        Size { width: 1290, height: 2796, name: "iphone-6.7-portrait".to_string() },
-        Size { width: 2796, height: 1290, name: "iphone-6.7-landscape".to_string() },
+        Size::new("iphone-6.7-portrait", 1290, 2796),
+        Size::new("iphone-6.7-landscape", 2796, 1290),
 
         // iPhone 15 / 14 / 13 / 12 / 11 Pro Max / XS Max
-        Size { width: 1242, height: 2688, name: "iphone-6.5-portrait".to_string() },
-        Size { width: 2688, height: 1242, name: "iphone-6.5-landscape".to_string() },
+        Size::new("iphone-6.5-portrait", 1242, 2688),
+        Size::new("iphone-6.5-landscape", 2688, 1242),
 
         // iPhone 14 Plus / 13 / 12 / 11 / XR
-        Size { width: 1242, height: 2208, name: "iphone-5.5-portrait".to_string() },
-        Size { width: 2208, height: 1242, name: "iphone-5.5-landscape".to_string() },
+        Size::new("iphone-5.5-portrait", 1242, 2208),
+        Size::new("iphone-5.5-landscape", 2208, 1242),
     ]
 }
 
@@ -77,46 +78,46 @@ pub fn screenshots_iphone() -> Vec<Size> {
 pub fn screenshots_ipad() -> Vec<Size> {
     vec![
         // iPad Pro 12.9" (3rd, 4th, 5th, 6th gen)
-        Size { width: 2048, height: 2732, name: "ipad-12.9-portrait".to_string() },
-        Size { width: 2732, height: 2048, name: "ipad-12.9-landscape".to_string() },
+        Size::new("ipad-12.9-portrait", 2048, 2732),
+        Size::new("ipad-12.9-landscape", 2732, 2048),
 
         // iPad Pro 11" / iPad Air
-        Size { width: 1668, height: 2388, name: "ipad-11-portrait".to_string() },
-        Size { width: 2388, height: 1668, name: "ipad-11-landscape".to_string() },
+        Size::new("ipad-11-portrait", 1668, 2388),
+        Size::new("ipad-11-landscape", 2388, 1668),
     ]
 }
 
 /// Apple Watch App Icons
 pub fn watch_icons() -> Vec<Size> {
     vec![
-        Size { width: 48, height: 48, name: "watch-24pt-2x-notification".to_string() },
-        Size { width: 55, height: 55, name: "watch-27.5pt-2x-notification".to_string() },
-        Size { width: 58, height: 58, name: "watch-29pt-2x-settings".to_string() },
-        Size { width: 87, height: 87, name: "watch-29pt-3x-settings".to_string() },
-        Size { width: 80, height: 80, name: "watch-40pt-2x-home".to_string() },
-        Size { width: 88, height: 88, name: "watch-44pt-2x-home".to_string() },
-        Size { width: 92, height: 92, name: "watch-46pt-2x-home".to_string() },
-        Size { width: 100, height: 100, name: "watch-50pt-2x-home".to_string() },
-        Size { width: 172, height: 172, name: "watch-86pt-2x-short-look".to_string() },
-        Size { width: 196, height: 196, name: "watch-98pt-2x-short-look".to_string() },
-        Size { width: 216, height: 216, name: "watch-108pt-2x-short-look".to_string() },
-        Size { width: 1024, height: 1024, name: "watch-app-store".to_string() },
+        Size::new("watch-24pt-2x-notification", 48, 48),
+        Size::new("watch-27.5pt-2x-notification", 55, 55),
+        Size::new("watch-29pt-2x-settings", 58, 58),
+        Size::new("watch-29pt-3x-settings", 87, 87),
+        Size::new("watch-40pt-2x-home", 80, 80),
+        Size::new("watch-44pt-2x-home", 88, 88),
+        Size::new("watch-46pt-2x-home", 92, 92),
+        Size::new("watch-50pt-2x-home", 100, 100),
+        Size::new("watch-86pt-2x-short-look", 172, 172),
+        Size::new("watch-98pt-2x-short-look", 196, 196),
+        Size::new("watch-108pt-2x-short-look", 216, 216),
+        Size::new("watch-app-store", 1024, 1024),
     ]
 }
 
 /// macOS App Icons
 pub fn macos_icons() -> Vec<Size> {
     vec![
-        Size { width: 16, height: 16, name: "mac-16pt-1x".to_string() },
-        Size { width: 32, height: 32, name: "mac-16pt-2x".to_string() },
-        Size { width: 32, height: 32, name: "mac-32pt-1x".to_string() },
-        Size { width: 64, height: 64, name: "mac-32pt-2x".to_string() },
-        Size { width: 128, height: 128, name: "mac-128pt-1x".to_string() },
-        Size { width: 256, height: 256, name: "mac-128pt-2x".to_string() },
-        Size { width: 256, height: 256, name: "mac-256pt-1x".to_string() },
-        Size { width: 512, height: 512, name: "mac-256pt-2x".to_string() },
-        Size { width: 512, height: 512, name: "mac-512pt-1x".to_string() },
-        Size { width: 1024, height: 1024, name: "mac-512pt-2x".to_string() },
+        Size::new("mac-16pt-1x", 16, 16),
+        Size::new("mac-16pt-2x", 32, 32),
+        Size::new("mac-32pt-1x", 32, 32),
+        Size::new("mac-32pt-2x", 64, 64),
+        Size::new("mac-128pt-1x", 128, 128),
+        Size::new("mac-128pt-2x", 256, 256),
+        Size::new("mac-256pt-1x", 256, 256),
+        Size::new("mac-256pt-2x", 512, 512),
+        Size::new("mac-512pt-1x", 512, 512),
+        Size::new("mac-512pt-2x", 1024, 1024),
     ]
 }
 
@@ -159,6 +160,118 @@ pub fn complete_apple_pack() -> Vec<Size> {
     sizes
 }
 
+/// Asset catalog metadata for an icon size, used to write `Contents.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppIconEntry {
+    pub idiom: &'static str,
+    /// Size in points, e.g. `83.5x83.5`
+    pub size: &'static str,
+    pub scale: &'static str,
+    pub role: Option<&'static str>,
+    pub subtype: Option<&'static str>,
+}
+
+impl AppIconEntry {
+    const fn new(idiom: &'static str, size: &'static str, scale: &'static str) -> Self {
+        AppIconEntry { idiom, size, scale, role: None, subtype: None }
+    }
+
+    const fn watch(size: &'static str, scale: &'static str, role: &'static str, subtype: Option<&'static str>) -> Self {
+        AppIconEntry { idiom: "watch", size, scale, role: Some(role), subtype }
+    }
+
+    /// Marketing icons are uploaded to the stores and must not contain transparency
+    pub fn is_marketing(&self) -> bool {
+        self.idiom.ends_with("-marketing")
+    }
+}
+
+/// Look up the `AppIcon.appiconset` entry for one of the icon sizes above.
+/// Screenshots and other non-icon sizes have no entry.
+pub fn app_icon_entry(name: &str) -> Option<AppIconEntry> {
+    let entry = match name {
+        // iPhone
+        "iphone-60pt-2x" => AppIconEntry::new("iphone", "60x60", "2x"),
+        "iphone-60pt-3x" => AppIconEntry::new("iphone", "60x60", "3x"),
+        "spotlight-iphone-40pt-2x" => AppIconEntry::new("iphone", "40x40", "2x"),
+        "spotlight-iphone-40pt-3x" => AppIconEntry::new("iphone", "40x40", "3x"),
+        "settings-iphone-29pt-2x" => AppIconEntry::new("iphone", "29x29", "2x"),
+        "settings-iphone-29pt-3x" => AppIconEntry::new("iphone", "29x29", "3x"),
+        "notification-iphone-20pt-2x" => AppIconEntry::new("iphone", "20x20", "2x"),
+        "notification-iphone-20pt-3x" => AppIconEntry::new("iphone", "20x20", "3x"),
+
+        // iPad
+        "ipad-76pt-1x" => AppIconEntry::new("ipad", "76x76", "1x"),
+        "ipad-76pt-2x" => AppIconEntry::new("ipad", "76x76", "2x"),
+        "ipad-pro-83.5pt-2x" => AppIconEntry::new("ipad", "83.5x83.5", "2x"),
+        "spotlight-ipad-40pt-1x" => AppIconEntry::new("ipad", "40x40", "1x"),
+        "spotlight-ipad-40pt-2x" => AppIconEntry::new("ipad", "40x40", "2x"),
+        "settings-ipad-29pt-1x" => AppIconEntry::new("ipad", "29x29", "1x"),
+        "settings-ipad-29pt-2x" => AppIconEntry::new("ipad", "29x29", "2x"),
+        "notification-ipad-20pt-1x" => AppIconEntry::new("ipad", "20x20", "1x"),
+        "notification-ipad-20pt-2x" => AppIconEntry::new("ipad", "20x20", "2x"),
+
+        // App Store
+        "app-store-1024pt" => AppIconEntry::new("ios-marketing", "1024x1024", "1x"),
+
+        // Apple Watch
+        "watch-24pt-2x-notification" => AppIconEntry::watch("24x24", "2x", "notificationCenter", Some("38mm")),
+        "watch-27.5pt-2x-notification" => AppIconEntry::watch("27.5x27.5", "2x", "notificationCenter", Some("42mm")),
+        "watch-29pt-2x-settings" => AppIconEntry::watch("29x29", "2x", "companionSettings", None),
+        "watch-29pt-3x-settings" => AppIconEntry::watch("29x29", "3x", "companionSettings", None),
+        "watch-40pt-2x-home" => AppIconEntry::watch("40x40", "2x", "appLauncher", Some("38mm")),
+        "watch-44pt-2x-home" => AppIconEntry::watch("44x44", "2x", "appLauncher", Some("40mm")),
+        "watch-46pt-2x-home" => AppIconEntry::watch("46x46", "2x", "appLauncher", Some("41mm")),
+        "watch-50pt-2x-home" => AppIconEntry::watch("50x50", "2x", "appLauncher", Some("44mm")),
+        "watch-86pt-2x-short-look" => AppIconEntry::watch("86x86", "2x", "quickLook", Some("38mm")),
+        "watch-98pt-2x-short-look" => AppIconEntry::watch("98x98", "2x", "quickLook", Some("42mm")),
+        "watch-108pt-2x-short-look" => AppIconEntry::watch("108x108", "2x", "quickLook", Some("44mm")),
+        "watch-app-store" => AppIconEntry::new("watch-marketing", "1024x1024", "1x"),
+
+        // macOS
+        "mac-16pt-1x" => AppIconEntry::new("mac", "16x16", "1x"),
+        "mac-16pt-2x" => AppIconEntry::new("mac", "16x16", "2x"),
+        "mac-32pt-1x" => AppIconEntry::new("mac", "32x32", "1x"),
+        "mac-32pt-2x" => AppIconEntry::new("mac", "32x32", "2x"),
+        "mac-128pt-1x" => AppIconEntry::new("mac", "128x128", "1x"),
+        "mac-128pt-2x" => AppIconEntry::new("mac", "128x128", "2x"),
+        "mac-256pt-1x" => AppIconEntry::new("mac", "256x256", "1x"),
+        "mac-256pt-2x" => AppIconEntry::new("mac", "256x256", "2x"),
+        "mac-512pt-1x" => AppIconEntry::new("mac", "512x512", "1x"),
+        "mac-512pt-2x" => AppIconEntry::new("mac", "512x512", "2x"),
+
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// Location of a macOS icon size inside an `.iconset` folder and `.icns` file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacIconEntry {
+    /// File name `iconutil` expects, e.g. `icon_16x16@2x.png`
+    pub filename: &'static str,
+    /// ICNS element type holding the PNG data
+    pub icns_type: [u8; 4],
+}
+
+/// Look up the `.iconset`/`.icns` entry for one of the macOS icon sizes
+pub fn mac_icon_entry(name: &str) -> Option<MacIconEntry> {
+    let (filename, icns_type) = match name {
+        "mac-16pt-1x" => ("icon_16x16.png", *b"icp4"),
+        "mac-16pt-2x" => ("icon_16x16@2x.png", *b"ic11"),
+        "mac-32pt-1x" => ("icon_32x32.png", *b"icp5"),
+        "mac-32pt-2x" => ("icon_32x32@2x.png", *b"ic12"),
+        "mac-128pt-1x" => ("icon_128x128.png", *b"ic07"),
+        "mac-128pt-2x" => ("icon_128x128@2x.png", *b"ic13"),
+        "mac-256pt-1x" => ("icon_256x256.png", *b"ic08"),
+        "mac-256pt-2x" => ("icon_256x256@2x.png", *b"ic14"),
+        "mac-512pt-1x" => ("icon_512x512.png", *b"ic09"),
+        "mac-512pt-2x" => ("icon_512x512@2x.png", *b"ic10"),
+        _ => return None,
+    };
+    Some(MacIconEntry { filename, icns_type })
+}
+
 /// iOS Preset categories for UI selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IOSPreset {
@@ -208,6 +321,23 @@ impl IOSPreset {
         }
     }
 
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            IOSPreset::AppIcons => "app-icons",
+            IOSPreset::SpotlightSettings => "spotlight-settings",
+            IOSPreset::NotificationIcons => "notification-icons",
+            IOSPreset::ScreenshotsIPhone => "screenshots-iphone",
+            IOSPreset::ScreenshotsIPad => "screenshots-ipad",
+            IOSPreset::WatchIcons => "watch-icons",
+            IOSPreset::MacOSIcons => "macos-icons",
+            IOSPreset::AllIOSIcons => "all-ios-icons",
+            IOSPreset::AllIOSScreenshots => "all-ios-screenshots",
+            IOSPreset::CompleteIOSPack => "complete-ios-pack",
+            IOSPreset::CompleteApplePack => "complete-apple-pack",
+        }
+    }
+
     pub fn all() -> Vec<IOSPreset> {
         vec![
             IOSPreset::CompleteApplePack,
@@ -224,3 +354,46 @@ impl IOSPreset {
         ]
     }
 }
+
+impl Preset for IOSPreset {
+    fn id(&self) -> &str {
+        IOSPreset::id(self)
+    }
+
+    fn name(&self) -> String {
+        gettextrs::gettext(IOSPreset::name(self))
+    }
+
+    fn sizes(&self) -> Vec<Size> {
+        self.get_sizes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{app_icon_entry, complete_apple_pack, mac_icon_entry, macos_icons};
+
+    #[test]
+    fn every_apple_icon_has_a_matching_catalog_entry() {
+        for size in complete_apple_pack() {
+            let entry = app_icon_entry(&size.name)
+                .unwrap_or_else(|| panic!("missing catalog entry for {}", size.name));
+
+            let points: f32 = entry.size.split('x').next().unwrap().parse().unwrap();
+            let scale: f32 = entry.scale.trim_end_matches('x').parse().unwrap();
+            assert_eq!((points * scale) as u32, size.width, "{}", size.name);
+        }
+    }
+
+    #[test]
+    fn every_macos_icon_has_an_iconset_entry() {
+        for size in macos_icons() {
+            let entry = mac_icon_entry(&size.name)
+                .unwrap_or_else(|| panic!("missing iconset entry for {}", size.name));
+            let catalog = app_icon_entry(&size.name).unwrap();
+
+            let suffix = if catalog.scale == "2x" { "@2x" } else { "" };
+            assert_eq!(entry.filename, format!("icon_{}{}.png", catalog.size, suffix));
+        }
+    }
+}
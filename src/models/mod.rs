@@ -2,8 +2,34 @@
 // By Leandro Santiago
 
 mod size;
+mod output_layout;
+mod output_format;
+mod encoder_settings;
+mod resize_mode;
+mod crop_hint;
+mod upscale_policy;
+mod resample_strategy;
+mod resize_backend;
+pub mod adaptive_icon;
 pub mod android_sizes;
 pub mod ios_sizes;
 pub mod generic_sizes;
+pub mod user_preset;
+pub mod platform;
 
-pub use size::Size;
+pub use size::{Size, largest_upscaled, merge_sizes, parse_custom_size, parse_size_list};
+pub use output_layout::{OutputLayout, is_valid_resource_name};
+pub use output_format::OutputFormat;
+pub use resize_mode::{ResizeMode, parse_padding};
+pub use crop_hint::CropHint;
+pub use upscale_policy::UpscalePolicy;
+pub use resample_strategy::ResampleStrategy;
+pub use resize_backend::ResizeBackend;
+pub use encoder_settings::{EncoderSettings, PngCompression, PngFilter, parse_quality};
+
+/// Marks a display name for the translation template without translating it.
+/// The name is passed through `gettext` where it is shown.
+#[allow(non_snake_case)]
+pub fn N_(text: &'static str) -> &'static str {
+    text
+}
@@ -0,0 +1,64 @@
+// Output Format Definitions
+// By Leandro Santiago
+
+use super::N_;
+
+/// Image format written for every generated size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Jpeg,
+    WebpLossy,
+    WebpLossless,
+    Avif,
+}
+
+impl OutputFormat {
+    pub fn name(&self) -> &str {
+        match self {
+            OutputFormat::Png => N_("PNG (lossless)"),
+            OutputFormat::Jpeg => N_("JPEG"),
+            OutputFormat::WebpLossy => N_("WebP"),
+            OutputFormat::WebpLossless => N_("WebP (lossless)"),
+            OutputFormat::Avif => N_("AVIF"),
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpeg",
+            OutputFormat::WebpLossy => "webp",
+            OutputFormat::WebpLossless => "webp-lossless",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::WebpLossy | OutputFormat::WebpLossless => "webp",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<OutputFormat> {
+        match id {
+            "jpg" => Some(OutputFormat::Jpeg),
+            _ => Self::all().into_iter().find(|format| format.id() == id),
+        }
+    }
+
+    pub fn all() -> Vec<OutputFormat> {
+        vec![
+            OutputFormat::Png,
+            OutputFormat::Jpeg,
+            OutputFormat::WebpLossy,
+            OutputFormat::WebpLossless,
+            OutputFormat::Avif,
+        ]
+    }
+}
@@ -0,0 +1,106 @@
+// Output Layout Definitions
+// By Leandro Santiago
+
+use super::adaptive_icon::AdaptiveIconOptions;
+use super::N_;
+
+/// Default Android resource name for launcher icons
+pub const DEFAULT_RESOURCE_NAME: &str = "ic_launcher";
+
+/// How generated files are arranged inside the output directory
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OutputLayout {
+    /// One folder per size, e.g. `xxhdpi/icon-xxhdpi.png`
+    #[default]
+    SizeFolders,
+    /// Xcode `AppIcon.appiconset` with `Contents.json`
+    AppIconSet,
+    /// Android project `res/mipmap-*/<resource_name>.png`, plus adaptive
+    /// icon layers and `mipmap-anydpi-v26` descriptors for adaptive sizes
+    AndroidRes { resource_name: String, adaptive: AdaptiveIconOptions },
+    /// Single multi-resolution `<name>.ico`, e.g. `favicon.ico`
+    Ico,
+    /// macOS `AppIcon.iconset` folder for `iconutil`
+    MacIconSet,
+    /// Native macOS `<name>.icns`
+    Icns,
+}
+
+impl OutputLayout {
+    pub fn name(&self) -> &str {
+        match self {
+            OutputLayout::SizeFolders => N_("One Folder per Size"),
+            OutputLayout::AppIconSet => N_("Xcode Asset Catalog (AppIcon.appiconset)"),
+            OutputLayout::AndroidRes { .. } => N_("Android Resources (res/mipmap-*)"),
+            OutputLayout::Ico => N_("Windows Icon (.ico)"),
+            OutputLayout::MacIconSet => N_("macOS Icon Set (AppIcon.iconset)"),
+            OutputLayout::Icns => N_("macOS Icon (.icns)"),
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            OutputLayout::SizeFolders => "folders",
+            OutputLayout::AppIconSet => "appiconset",
+            OutputLayout::AndroidRes { .. } => "android-res",
+            OutputLayout::Ico => "ico",
+            OutputLayout::MacIconSet => "iconset",
+            OutputLayout::Icns => "icns",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<OutputLayout> {
+        Self::all().into_iter().find(|layout| layout.id() == id)
+    }
+
+    pub fn all() -> Vec<OutputLayout> {
+        vec![
+            OutputLayout::SizeFolders,
+            OutputLayout::AppIconSet,
+            OutputLayout::android_res(),
+            OutputLayout::Ico,
+            OutputLayout::MacIconSet,
+            OutputLayout::Icns,
+        ]
+    }
+
+    /// Whether every input writes the same file names, so batches need a
+    /// subfolder per input to keep them apart
+    pub fn has_fixed_file_names(&self) -> bool {
+        matches!(
+            self,
+            OutputLayout::AppIconSet | OutputLayout::AndroidRes { .. } | OutputLayout::MacIconSet
+        )
+    }
+
+    /// Android resources layout using the default `ic_launcher` name
+    pub fn android_res() -> OutputLayout {
+        OutputLayout::AndroidRes {
+            resource_name: DEFAULT_RESOURCE_NAME.to_string(),
+            adaptive: AdaptiveIconOptions::default(),
+        }
+    }
+}
+
+/// Android resource names may only contain lowercase letters, digits and
+/// underscores, and must start with a letter
+pub fn is_valid_resource_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_resource_name;
+
+    #[test]
+    fn validates_android_resource_names() {
+        assert!(is_valid_resource_name("ic_launcher"));
+        assert!(is_valid_resource_name("app_icon2"));
+        assert!(!is_valid_resource_name(""));
+        assert!(!is_valid_resource_name("IcLauncher"));
+        assert!(!is_valid_resource_name("ic-launcher"));
+        assert!(!is_valid_resource_name("2icon"));
+    }
+}
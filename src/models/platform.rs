@@ -0,0 +1,163 @@
+// Platform Definitions
+// By Leandro Santiago
+
+use gettextrs::gettext;
+
+use super::android_sizes::AndroidPreset;
+use super::generic_sizes::GenericPreset;
+use super::ios_sizes::IOSPreset;
+use super::user_preset::UserPreset;
+use super::{OutputFormat, OutputLayout, Size};
+
+/// Id of the platform that only offers custom sizes
+pub const CUSTOM_PLATFORM_ID: &str = "custom";
+
+/// A named list of sizes offered under a platform
+pub trait Preset: std::fmt::Debug {
+    /// Stable identifier, unique within its platform
+    fn id(&self) -> &str;
+
+    /// Name shown in the interface, translated when possible
+    fn name(&self) -> String;
+
+    fn sizes(&self) -> Vec<Size>;
+
+    /// Format selected when the preset is picked
+    fn output_format(&self) -> Option<OutputFormat> {
+        None
+    }
+
+    /// Folder layout selected when the preset is picked
+    fn layout(&self) -> Option<OutputLayout> {
+        None
+    }
+}
+
+/// A group of presets with the folder layouts that make sense for them
+#[derive(Debug)]
+pub struct Platform {
+    /// Stable identifier, used before the slash in preset ids
+    pub id: &'static str,
+    pub name: String,
+    /// Empty for the platform that only offers custom sizes
+    pub presets: Vec<Box<dyn Preset>>,
+    /// Layouts offered for these presets, the first one is the default
+    pub layouts: Vec<OutputLayout>,
+}
+
+impl Platform {
+    fn new<P: Preset + 'static>(id: &'static str, name: String, presets: Vec<P>, layouts: Vec<OutputLayout>) -> Self {
+        Platform {
+            id,
+            name,
+            presets: presets.into_iter().map(|preset| Box::new(preset) as Box<dyn Preset>).collect(),
+            layouts,
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.id == CUSTOM_PLATFORM_ID
+    }
+
+    pub fn preset(&self, id: &str) -> Option<&dyn Preset> {
+        self.presets.iter().find(|preset| preset.id() == id).map(|preset| preset.as_ref())
+    }
+
+    /// `<platform>/<preset>` id of one of this platform's presets
+    pub fn preset_id(&self, preset: &dyn Preset) -> String {
+        format!("{}/{}", self.id, preset.id())
+    }
+}
+
+/// Every platform in the order shown in the interface. "My Presets" is only
+/// included when the user has presets of their own.
+pub fn platforms(user_presets: Vec<UserPreset>) -> Vec<Platform> {
+    let mut platforms = vec![
+        Platform::new(
+            "android",
+            gettext("Android"),
+            AndroidPreset::all(),
+            vec![OutputLayout::SizeFolders, OutputLayout::android_res()],
+        ),
+        Platform::new(
+            "ios",
+            gettext("iOS / Apple"),
+            IOSPreset::all(),
+            vec![
+                OutputLayout::SizeFolders,
+                OutputLayout::AppIconSet,
+                OutputLayout::MacIconSet,
+                OutputLayout::Icns,
+            ],
+        ),
+        Platform::new(
+            "web",
+            gettext("Web & Social Media"),
+            GenericPreset::all(),
+            vec![OutputLayout::SizeFolders, OutputLayout::Ico],
+        ),
+        Platform::new::<UserPreset>(
+            CUSTOM_PLATFORM_ID,
+            gettext("Custom"),
+            Vec::new(),
+            vec![OutputLayout::SizeFolders],
+        ),
+    ];
+
+    if !user_presets.is_empty() {
+        platforms.push(Platform::new("user", gettext("My Presets"), user_presets, OutputLayout::all()));
+    }
+
+    platforms
+}
+
+/// Look up a preset by `<platform>/<preset>` id, e.g. `android/launcher-icons`
+pub fn find_preset<'a>(platforms: &'a [Platform], id: &str) -> Option<&'a dyn Preset> {
+    let (platform, preset) = id.split_once('/')?;
+    platforms
+        .iter()
+        .find(|candidate| candidate.id == platform)?
+        .preset(preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_preset, platforms};
+    use crate::models::user_preset::UserPreset;
+    use crate::models::{OutputFormat, Size};
+
+    #[test]
+    fn finds_presets_by_id() {
+        let user_preset = UserPreset {
+            id: "banners".to_string(),
+            name: "Banners".to_string(),
+            sizes: vec![Size::new("hero", 1920, 600)],
+            output_format: Some(OutputFormat::Jpeg),
+            layout: None,
+        };
+
+        let without_user = platforms(Vec::new());
+        assert!(without_user.iter().all(|platform| platform.id != "user"));
+        assert!(without_user.iter().any(|platform| platform.is_custom() && platform.presets.is_empty()));
+
+        let platforms = platforms(vec![user_preset]);
+        for platform in &platforms {
+            assert!(!platform.layouts.is_empty(), "{}", platform.id);
+            for preset in &platform.presets {
+                let id = platform.preset_id(preset.as_ref());
+                assert_eq!(find_preset(&platforms, &id).unwrap().id(), preset.id());
+            }
+        }
+
+        let launcher = find_preset(&platforms, "android/launcher-icons").unwrap();
+        assert_eq!(launcher.sizes().len(), 6);
+        assert_eq!(launcher.output_format(), None);
+
+        let banners = find_preset(&platforms, "user/banners").unwrap();
+        assert_eq!(banners.output_format(), Some(OutputFormat::Jpeg));
+        assert_eq!(banners.sizes()[0].name, "hero");
+
+        assert!(find_preset(&platforms, "android/unknown").is_none());
+        assert!(find_preset(&platforms, "launcher-icons").is_none());
+    }
+}
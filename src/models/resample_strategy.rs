@@ -0,0 +1,54 @@
+// Resample Strategy Definitions
+// By Leandro Santiago
+
+/// Where each size is resampled from. Both give the same dimensions;
+/// progressive output differs from direct by less than one level per channel
+/// on average, though single pixels along sharp edges can differ more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleStrategy {
+    /// Resample every size from the full-resolution image
+    #[default]
+    Direct,
+    /// Halve the image step by step and resample each size from the
+    /// smallest copy still at least twice its resolution
+    Progressive,
+}
+
+impl ResampleStrategy {
+    pub fn name(&self) -> &str {
+        match self {
+            ResampleStrategy::Direct => "Direct",
+            ResampleStrategy::Progressive => "Progressive",
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            ResampleStrategy::Direct => "direct",
+            ResampleStrategy::Progressive => "progressive",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ResampleStrategy> {
+        Self::all().into_iter().find(|strategy| strategy.id() == id)
+    }
+
+    pub fn all() -> Vec<ResampleStrategy> {
+        vec![ResampleStrategy::Direct, ResampleStrategy::Progressive]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResampleStrategy;
+
+    #[test]
+    fn parses_strategies() {
+        for strategy in ResampleStrategy::all() {
+            assert_eq!(ResampleStrategy::from_id(strategy.id()), Some(strategy));
+        }
+        assert_eq!(ResampleStrategy::default(), ResampleStrategy::Direct);
+        assert!(ResampleStrategy::from_id("fast").is_none());
+    }
+}
@@ -0,0 +1,52 @@
+// Resize Backend Definitions
+// By Leandro Santiago
+
+/// Implementation used to resample images. Both use a Lanczos3 filter and
+/// give the same dimensions; pixels may differ by a level here and there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResizeBackend {
+    /// The `image` crate's resampler, kept as the reference
+    #[default]
+    Reference,
+    /// Vectorized resampler that filters all four channels of a pixel at once
+    Simd,
+}
+
+impl ResizeBackend {
+    pub fn name(&self) -> &str {
+        match self {
+            ResizeBackend::Reference => "Reference",
+            ResizeBackend::Simd => "SIMD",
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            ResizeBackend::Reference => "reference",
+            ResizeBackend::Simd => "simd",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ResizeBackend> {
+        Self::all().into_iter().find(|backend| backend.id() == id)
+    }
+
+    pub fn all() -> Vec<ResizeBackend> {
+        vec![ResizeBackend::Reference, ResizeBackend::Simd]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResizeBackend;
+
+    #[test]
+    fn parses_backends() {
+        for backend in ResizeBackend::all() {
+            assert_eq!(ResizeBackend::from_id(backend.id()), Some(backend));
+        }
+        assert_eq!(ResizeBackend::default(), ResizeBackend::Reference);
+        assert!(ResizeBackend::from_id("gpu").is_none());
+    }
+}
@@ -0,0 +1,85 @@
+// Resize Mode Definitions
+// By Leandro Santiago
+
+use super::adaptive_icon::parse_hex_color;
+use super::N_;
+
+pub const TRANSPARENT: [u8; 4] = [0, 0, 0, 0];
+
+/// How an image is fitted into a size that has both width and height.
+/// Sizes with a single dimension always keep the source aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResizeMode {
+    /// Keep the aspect ratio inside the box; the result may be smaller
+    Fit,
+    /// Fit inside the box and pad the rest with an RGBA color
+    Contain([u8; 4]),
+    /// Fill the box and crop what overflows, keeping the centre. The
+    /// default, so presets come out at their exact dimensions.
+    #[default]
+    Cover,
+    /// Scale both axes independently to the exact size
+    Stretch,
+}
+
+impl ResizeMode {
+    pub fn name(&self) -> &str {
+        match self {
+            ResizeMode::Fit => N_("Fit (keep aspect ratio)"),
+            ResizeMode::Contain(_) => N_("Contain (pad to size)"),
+            ResizeMode::Cover => N_("Cover (crop to size)"),
+            ResizeMode::Stretch => N_("Stretch"),
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            ResizeMode::Fit => "fit",
+            ResizeMode::Contain(_) => "contain",
+            ResizeMode::Cover => "cover",
+            ResizeMode::Stretch => "stretch",
+        }
+    }
+
+    /// `contain` starts with transparent padding
+    pub fn from_id(id: &str) -> Option<ResizeMode> {
+        Self::all().into_iter().find(|mode| mode.id() == id)
+    }
+
+    pub fn all() -> Vec<ResizeMode> {
+        vec![
+            ResizeMode::Fit,
+            ResizeMode::Contain(TRANSPARENT),
+            ResizeMode::Cover,
+            ResizeMode::Stretch,
+        ]
+    }
+}
+
+/// Parse `transparent` or an opaque `#RRGGBB` padding color
+pub fn parse_padding(text: &str) -> Option<[u8; 4]> {
+    if text.trim().eq_ignore_ascii_case("transparent") {
+        return Some(TRANSPARENT);
+    }
+    let [r, g, b] = parse_hex_color(text)?;
+    Some([r, g, b, 0xFF])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_padding, ResizeMode, TRANSPARENT};
+
+    #[test]
+    fn parses_modes_and_padding() {
+        for mode in ResizeMode::all() {
+            assert_eq!(ResizeMode::from_id(mode.id()), Some(mode));
+        }
+        assert_eq!(ResizeMode::from_id("contain"), Some(ResizeMode::Contain(TRANSPARENT)));
+        assert!(ResizeMode::from_id("fill").is_none());
+
+        assert_eq!(parse_padding("Transparent"), Some(TRANSPARENT));
+        assert_eq!(parse_padding("#3DDC84"), Some([0x3D, 0xDC, 0x84, 0xFF]));
+        assert!(parse_padding("white").is_none());
+    }
+}
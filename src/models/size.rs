@@ -2,9 +2,266 @@
 //  By Leandro Santiago
 //  Define the struct for the images
 
+use super::user_preset::is_valid_size_name;
+use super::{CropHint, ResizeMode};
+
 #[derive(Clone, Debug)]
 pub struct Size {
     pub width: u32,
     pub height: u32,
-    pub name: String
+    pub name: String,
+    /// Overrides the resize mode chosen for the whole run
+    pub mode: Option<ResizeMode>,
+}
+
+impl Size {
+    /// A size that follows the run's resize mode
+    pub fn new(name: impl Into<String>, width: u32, height: u32) -> Self {
+        Size { width, height, name: name.into(), mode: None }
+    }
+
+    /// The mode this size is resized with, its own or `default_mode`. A crop
+    /// hint asks for part of the image, so it turns a run-wide fit into cover;
+    /// a size with its own mode keeps it.
+    pub fn resize_mode(&self, default_mode: ResizeMode, crop: Option<CropHint>) -> ResizeMode {
+        match (self.mode, default_mode) {
+            (Some(mode), _) => mode,
+            (None, ResizeMode::Fit) if crop.is_some() => ResizeMode::Cover,
+            (None, mode) => mode,
+        }
+    }
+
+    /// Whether resizing a `width`×`height` source to this size enlarges it
+    pub fn upscales(&self, width: u32, height: u32, default_mode: ResizeMode, crop: Option<CropHint>) -> bool {
+        match (self.width, self.height) {
+            (target_width, 0) => target_width > width,
+            (0, target_height) => target_height > height,
+            (target_width, target_height) => match self.resize_mode(default_mode, crop) {
+                // The image is scaled by the smaller of the two ratios
+                ResizeMode::Fit | ResizeMode::Contain(_) => target_width > width && target_height > height,
+                ResizeMode::Cover | ResizeMode::Stretch => target_width > width || target_height > height,
+            },
+        }
+    }
+}
+
+/// The largest of `sizes` that a `width`×`height` source is too small for
+pub fn largest_upscaled(
+    sizes: &[Size],
+    width: u32,
+    height: u32,
+    default_mode: ResizeMode,
+    crop: Option<CropHint>,
+) -> Option<&Size> {
+    sizes
+        .iter()
+        .filter(|size| size.upscales(width, height, default_mode, crop))
+        .max_by_key(|size| (size.width.max(size.height), u64::from(size.width) * u64::from(size.height)))
+}
+
+/// A zero dimension is resolved from the source image's aspect ratio by the processor.
+pub fn parse_custom_size(width_text: &str, height_text: &str) -> Option<Size> {
+    let parse_dimension = |text: &str| {
+        if text.trim().is_empty() {
+            Some(0)
+        } else {
+            text.trim().parse::<u32>().ok().filter(|value| *value > 0)
+        }
+    };
+
+    let width = parse_dimension(width_text)?;
+    let height = parse_dimension(height_text)?;
+
+    if width == 0 && height == 0 {
+        return None;
+    }
+
+    let name = match (width, height) {
+        (width, 0) => format!("Custom_{}w", width),
+        (0, height) => format!("Custom_{}h", height),
+        (width, height) => format!("Custom_{}x{}", width, height),
+    };
+
+    Some(Size::new(name, width, height))
+}
+
+/// Parse a `WxH` specification such as `1920x1080`, `1920x` or `x1080`,
+/// optionally followed by a resize mode: `1024x500:cover`, and preceded by
+/// the folder name to use instead of `Custom_1920x1080`: `hero=1920x600`.
+pub fn parse_size_spec(spec: &str) -> Option<Size> {
+    let (name, spec) = match spec.split_once('=') {
+        Some((name, spec)) => (Some(name.trim()), spec),
+        None => (None, spec),
+    };
+
+    let (dimensions, mode) = match spec.trim().split_once(':') {
+        Some((dimensions, mode)) => (dimensions, Some(ResizeMode::from_id(mode.trim())?)),
+        None => (spec.trim(), None),
+    };
+
+    let (width_text, height_text) = dimensions.split_once(['x', 'X'])?;
+    let mut size = parse_custom_size(width_text, height_text)?;
+    size.mode = mode;
+
+    if let Some(name) = name {
+        if !is_valid_size_name(name) {
+            return None;
+        }
+        size.name = name.to_string();
+    }
+    Some(size)
+}
+
+/// Parse a comma separated list of size specifications such as
+/// `1920x1080, 1280x, thumb=x720`. On failure returns the first bad entry.
+pub fn parse_size_list(text: &str) -> Result<Vec<Size>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(|spec| parse_size_spec(spec).ok_or_else(|| spec.to_string()))
+        .collect()
+}
+
+/// Combine the sizes of several presets into one run. Sizes with the same
+/// name and dimensions are generated once; a name reused for different
+/// dimensions gets the dimensions appended, and a number if that is taken
+/// too, so no output overwrites another.
+pub fn merge_sizes(sizes: Vec<Size>) -> Vec<Size> {
+    let mut merged: Vec<Size> = Vec::new();
+
+    'sizes: for mut size in sizes {
+        let name = size.name.clone();
+        let mut attempt = 1;
+        while let Some(other) = merged.iter().find(|other| other.name == size.name) {
+            if other.width == size.width && other.height == size.height {
+                continue 'sizes;
+            }
+            size.name = match attempt {
+                1 => format!("{}-{}x{}", name, size.width, size.height),
+                n => format!("{}-{}x{}-{}", name, size.width, size.height, n),
+            };
+            attempt += 1;
+        }
+        merged.push(size);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{largest_upscaled, merge_sizes, parse_custom_size, parse_size_list, parse_size_spec, Size};
+    use crate::models::{CropHint, ResizeMode};
+
+    #[test]
+    fn accepts_either_or_both_custom_dimensions() {
+        let width_only = parse_custom_size("1920", "").unwrap();
+        assert_eq!((width_only.width, width_only.height), (1920, 0));
+
+        let height_only = parse_custom_size("", "1080").unwrap();
+        assert_eq!((height_only.width, height_only.height), (0, 1080));
+
+        let both = parse_custom_size("1920", "1080").unwrap();
+        assert_eq!((both.width, both.height), (1920, 1080));
+    }
+
+    #[test]
+    fn rejects_empty_zero_or_invalid_dimensions() {
+        assert!(parse_custom_size("", "").is_none());
+        assert!(parse_custom_size("0", "1080").is_none());
+        assert!(parse_custom_size("wide", "1080").is_none());
+    }
+
+    #[test]
+    fn parses_size_specs() {
+        let both = parse_size_spec("1920x1080").unwrap();
+        assert_eq!((both.width, both.height), (1920, 1080));
+        assert_eq!(both.name, "Custom_1920x1080");
+
+        let width_only = parse_size_spec("512X").unwrap();
+        assert_eq!((width_only.width, width_only.height), (512, 0));
+
+        let height_only = parse_size_spec("x720").unwrap();
+        assert_eq!((height_only.width, height_only.height), (0, 720));
+
+        let cover = parse_size_spec("1024x500:cover").unwrap();
+        assert_eq!((cover.width, cover.height), (1024, 500));
+        assert_eq!(cover.mode, Some(ResizeMode::Cover));
+        assert!(both.mode.is_none());
+
+        let named = parse_size_spec(" hero = 1920x600:cover").unwrap();
+        assert_eq!(named.name, "hero");
+        assert_eq!(named.mode, Some(ResizeMode::Cover));
+
+        assert!(parse_size_spec("../hero=1920x600").is_none());
+        assert!(parse_size_spec("=1920x600").is_none());
+        assert!(parse_size_spec("1024x500:zoom").is_none());
+        assert!(parse_size_spec("1920").is_none());
+        assert!(parse_size_spec("x").is_none());
+    }
+
+    #[test]
+    fn parses_size_lists() {
+        let sizes = parse_size_list("1920x1080, 1280x,thumb=x720,").unwrap();
+        let names: Vec<&str> = sizes.iter().map(|size| size.name.as_str()).collect();
+        assert_eq!(names, ["Custom_1920x1080", "Custom_1280w", "thumb"]);
+        assert_eq!((sizes[2].width, sizes[2].height), (0, 720));
+
+        assert_eq!(parse_size_list("1920x1080, wide").unwrap_err(), "wide");
+        assert!(parse_size_list(" ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn merges_sizes_of_several_presets() {
+        let size = Size::new;
+
+        let merged = merge_sizes(vec![
+            size("mdpi", 48, 48),
+            size("hdpi", 72, 72),
+            size("mdpi", 48, 48),
+            size("icon", 512, 512),
+            size("icon", 1024, 1024),
+            size("icon", 1024, 1024),
+        ]);
+
+        let names: Vec<&str> = merged.iter().map(|size| size.name.as_str()).collect();
+        assert_eq!(names, ["mdpi", "hdpi", "icon", "icon-1024x1024"]);
+        assert_eq!(merged[3].width, 1024);
+
+        // A renamed size never replaces one that already has the new name
+        let merged = merge_sizes(vec![
+            size("icon-64x64", 32, 32),
+            size("icon", 16, 16),
+            size("icon", 64, 64),
+            size("icon", 64, 64),
+        ]);
+        let names: Vec<(&str, u32)> = merged.iter().map(|size| (size.name.as_str(), size.width)).collect();
+        assert_eq!(names, [("icon-64x64", 32), ("icon", 16), ("icon-64x64-2", 64)]);
+    }
+
+    #[test]
+    fn finds_the_largest_size_a_source_is_too_small_for() {
+        let sizes = parse_size_list("48x48, icon=512x512, banner=1024x500:cover, x720").unwrap();
+
+        assert_eq!(largest_upscaled(&sizes, 600, 600, ResizeMode::Fit, None).unwrap().name, "banner");
+        assert_eq!(largest_upscaled(&sizes, 1024, 600, ResizeMode::Fit, None).unwrap().name, "Custom_720h");
+        assert!(largest_upscaled(&sizes, 1024, 1024, ResizeMode::Fit, None).is_none());
+
+        // Fit only enlarges when both sides are too small, cover and stretch when either is
+        let wide = parse_size_spec("1024x500").unwrap();
+        assert!(!wide.upscales(2000, 400, ResizeMode::Fit, None));
+        assert!(wide.upscales(2000, 400, ResizeMode::Cover, None));
+        assert!(wide.upscales(2000, 400, ResizeMode::Stretch, None));
+        assert!(wide.upscales(800, 400, ResizeMode::Contain([0, 0, 0, 0]), None));
+        assert!(!sizes[0].upscales(48, 48, ResizeMode::Stretch, None));
+
+        // A crop hint turns a run-wide fit into cover, but not a size's own fit
+        let hint = Some(CropHint::FocalPoint { x: 0.5, y: 0.5 });
+        assert!(wide.upscales(2000, 400, ResizeMode::Fit, hint));
+        assert_eq!(wide.resize_mode(ResizeMode::Fit, hint), ResizeMode::Cover);
+        assert_eq!(wide.resize_mode(ResizeMode::Stretch, hint), ResizeMode::Stretch);
+        let own_fit = parse_size_spec("1024x500:fit").unwrap();
+        assert!(!own_fit.upscales(2000, 400, ResizeMode::Cover, hint));
+        assert_eq!(own_fit.resize_mode(ResizeMode::Cover, hint), ResizeMode::Fit);
+    }
 }
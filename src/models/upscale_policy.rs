@@ -0,0 +1,67 @@
+// Upscale Policy Definitions
+// By Leandro Santiago
+
+use super::N_;
+
+/// What to do with sizes larger than the source image. Enlarging with
+/// Lanczos3 works but gives blurry results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpscalePolicy {
+    /// Enlarge the image without saying anything
+    Allow,
+    /// Enlarge the image and report the sizes affected
+    #[default]
+    Warn,
+    /// Leave out the sizes larger than the source
+    Skip,
+    /// Fail the whole image without writing any of its sizes
+    Fail,
+}
+
+impl UpscalePolicy {
+    pub fn name(&self) -> &str {
+        match self {
+            UpscalePolicy::Allow => N_("Allow"),
+            UpscalePolicy::Warn => N_("Allow and warn"),
+            UpscalePolicy::Skip => N_("Skip larger sizes"),
+            UpscalePolicy::Fail => N_("Fail the image"),
+        }
+    }
+
+    /// Stable identifier used on the command line
+    pub fn id(&self) -> &str {
+        match self {
+            UpscalePolicy::Allow => "allow",
+            UpscalePolicy::Warn => "warn",
+            UpscalePolicy::Skip => "skip",
+            UpscalePolicy::Fail => "fail",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<UpscalePolicy> {
+        Self::all().into_iter().find(|policy| policy.id() == id)
+    }
+
+    pub fn all() -> Vec<UpscalePolicy> {
+        vec![
+            UpscalePolicy::Allow,
+            UpscalePolicy::Warn,
+            UpscalePolicy::Skip,
+            UpscalePolicy::Fail,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UpscalePolicy;
+
+    #[test]
+    fn parses_policies() {
+        for policy in UpscalePolicy::all() {
+            assert_eq!(UpscalePolicy::from_id(policy.id()), Some(policy));
+        }
+        assert_eq!(UpscalePolicy::default(), UpscalePolicy::Warn);
+        assert!(UpscalePolicy::from_id("never").is_none());
+    }
+}
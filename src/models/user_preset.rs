@@ -0,0 +1,211 @@
+// User Preset Definitions
+// By Leandro Santiago
+
+use gettextrs::gettext;
+use serde::{Deserialize, Serialize};
+
+use super::platform::Preset;
+use super::{OutputFormat, OutputLayout, ResizeMode, Size};
+
+/// A preset read from a file in the user's presets folder
+#[derive(Debug, Clone)]
+pub struct UserPreset {
+    /// File name without extension, unique within the presets folder
+    pub id: String,
+    pub name: String,
+    pub sizes: Vec<Size>,
+    /// Format selected when the preset is picked, if the file sets one
+    pub output_format: Option<OutputFormat>,
+    /// Folder layout selected when the preset is picked, if the file sets one
+    pub layout: Option<OutputLayout>,
+}
+
+/// On-disk form of a preset. Formats, layouts and resize modes are stored
+/// by the same ids the command line uses.
+///
+/// ```toml
+/// name = "Company Banners"
+/// format = "jpeg"
+///
+/// [[sizes]]
+/// name = "hero"
+/// width = 1920
+/// height = 600
+/// mode = "cover"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PresetFile {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    pub sizes: Vec<PresetFileSize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PresetFileSize {
+    pub name: String,
+    /// 0 or missing keeps the aspect ratio
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+impl UserPreset {
+    pub fn from_file(id: &str, file: PresetFile) -> Result<UserPreset, String> {
+        if file.name.trim().is_empty() {
+            return Err(gettext("The preset has no name"));
+        }
+        if file.sizes.is_empty() {
+            return Err(gettext("The preset has no sizes"));
+        }
+
+        let output_format = match &file.format {
+            Some(id) => Some(OutputFormat::from_id(id)
+                .ok_or_else(|| format!("{} {}", gettext("Unknown output format:"), id))?),
+            None => None,
+        };
+        let layout = match &file.layout {
+            Some(id) => Some(OutputLayout::from_id(id)
+                .ok_or_else(|| format!("{} {}", gettext("Unknown layout:"), id))?),
+            None => None,
+        };
+
+        let mut sizes = Vec::new();
+        for entry in file.sizes {
+            if !is_valid_size_name(&entry.name) {
+                return Err(format!("{} {}", gettext("Invalid size name:"), entry.name));
+            }
+            if entry.width == 0 && entry.height == 0 {
+                return Err(gettext("Size “{}” needs a width, a height or both").replace("{}", &entry.name));
+            }
+            let mode = match &entry.mode {
+                Some(id) => Some(ResizeMode::from_id(id)
+                    .ok_or_else(|| format!("{} {}", gettext("Unknown resize mode:"), id))?),
+                None => None,
+            };
+
+            sizes.push(Size {
+                width: entry.width,
+                height: entry.height,
+                name: entry.name,
+                mode,
+            });
+        }
+
+        Ok(UserPreset {
+            id: id.to_string(),
+            name: file.name.trim().to_string(),
+            sizes,
+            output_format,
+            layout,
+        })
+    }
+
+    pub fn to_file(&self) -> PresetFile {
+        PresetFile {
+            name: self.name.clone(),
+            format: self.output_format.map(|format| format.id().to_string()),
+            layout: self.layout.as_ref().map(|layout| layout.id().to_string()),
+            sizes: self
+                .sizes
+                .iter()
+                .map(|size| PresetFileSize {
+                    name: size.name.clone(),
+                    width: size.width,
+                    height: size.height,
+                    mode: size.mode.map(|mode| mode.id().to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Preset for UserPreset {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn sizes(&self) -> Vec<Size> {
+        self.sizes.clone()
+    }
+
+    fn output_format(&self) -> Option<OutputFormat> {
+        self.output_format
+    }
+
+    fn layout(&self) -> Option<OutputLayout> {
+        self.layout.clone()
+    }
+}
+
+/// Size names become folder and file names, so they must not contain paths
+pub fn is_valid_size_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_size_name, PresetFile, PresetFileSize, UserPreset};
+    use crate::models::{OutputFormat, ResizeMode};
+
+    fn file(format: Option<&str>, sizes: Vec<PresetFileSize>) -> PresetFile {
+        PresetFile {
+            name: "Banners".to_string(),
+            format: format.map(str::to_string),
+            layout: None,
+            sizes,
+        }
+    }
+
+    fn size(name: &str, width: u32, height: u32, mode: Option<&str>) -> PresetFileSize {
+        PresetFileSize {
+            name: name.to_string(),
+            width,
+            height,
+            mode: mode.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn converts_files_and_back() {
+        let preset = UserPreset::from_file(
+            "banners",
+            file(Some("jpg"), vec![size("hero", 1920, 600, Some("cover")), size("wide", 1200, 0, None)]),
+        )
+        .unwrap();
+
+        assert_eq!(preset.output_format, Some(OutputFormat::Jpeg));
+        assert_eq!(preset.sizes[0].mode, Some(ResizeMode::Cover));
+        assert_eq!((preset.sizes[1].width, preset.sizes[1].height), (1200, 0));
+
+        let saved = preset.to_file();
+        assert_eq!(saved.format.as_deref(), Some("jpeg"));
+        assert_eq!(saved.sizes[0].mode.as_deref(), Some("cover"));
+    }
+
+    #[test]
+    fn rejects_invalid_presets() {
+        assert!(UserPreset::from_file("empty", file(None, vec![])).is_err());
+        assert!(UserPreset::from_file("format", file(Some("tga"), vec![size("a", 1, 1, None)])).is_err());
+        assert!(UserPreset::from_file("zero", file(None, vec![size("a", 0, 0, None)])).is_err());
+        assert!(UserPreset::from_file("mode", file(None, vec![size("a", 1, 1, Some("zoom"))])).is_err());
+
+        assert!(is_valid_size_name("hero-1920"));
+        assert!(!is_valid_size_name("../hero"));
+        assert!(!is_valid_size_name(" "));
+    }
+}
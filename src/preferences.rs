@@ -28,6 +28,8 @@ use adw::subclass::prelude::*;
 use adw::prelude::*;
 use gtk::{gio, glib};
 
+use crate::models::{EncoderSettings, PngCompression, PngFilter, ResampleStrategy, ResizeBackend};
+
 mod imp {
     use super::*;
 
@@ -36,6 +38,22 @@ mod imp {
     pub struct JairPreferencesWindow {
         #[template_child]
         pub language_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub jpeg_quality_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub jpeg_progressive_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub png_compression_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub png_filter_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub webp_quality_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub progressive_resample_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub simd_resize_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub threads_row: TemplateChild<adw::SpinRow>,
     }
 
     #[glib::object_subclass]
@@ -58,6 +76,7 @@ mod imp {
             self.parent_constructed();
             let obj = self.obj();
             obj.setup_language_combo();
+            obj.setup_encoding_rows();
         }
     }
 
@@ -133,4 +152,117 @@ impl JairPreferencesWindow {
             }
         }));
     }
-}
\ No newline at end of file
+
+    fn setup_encoding_rows(&self) {
+        let imp = self.imp();
+        let settings = gio::Settings::new("codes.lsb.jair");
+        let current = load_encoder_settings();
+
+        let compressions = gtk::StringList::new(&[]);
+        for compression in PngCompression::all() {
+            compressions.append(&gettextrs::gettext(compression.name()));
+        }
+        imp.png_compression_combo.set_model(Some(&compressions));
+
+        let filters = gtk::StringList::new(&[]);
+        for filter in PngFilter::all() {
+            filters.append(&gettextrs::gettext(filter.name()));
+        }
+        imp.png_filter_combo.set_model(Some(&filters));
+
+        imp.jpeg_quality_row.set_value(f64::from(current.jpeg_quality));
+        imp.jpeg_progressive_row.set_active(current.jpeg_progressive);
+        let compression_index = PngCompression::all()
+            .iter()
+            .position(|compression| *compression == current.png_compression)
+            .unwrap_or(0);
+        imp.png_compression_combo.set_selected(compression_index as u32);
+        let filter_index = PngFilter::all()
+            .iter()
+            .position(|filter| *filter == current.png_filter)
+            .unwrap_or(0);
+        imp.png_filter_combo.set_selected(filter_index as u32);
+        imp.webp_quality_row.set_value(f64::from(current.webp_quality));
+        imp.progressive_resample_row.set_active(load_resample_strategy() == ResampleStrategy::Progressive);
+        imp.simd_resize_row.set_active(load_resize_backend() == ResizeBackend::Simd);
+        imp.threads_row.set_value(load_thread_count() as f64);
+
+        imp.jpeg_progressive_row.connect_active_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_boolean("jpeg-progressive", row.is_active());
+        }));
+
+        imp.jpeg_quality_row.connect_value_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_int("jpeg-quality", row.value() as i32);
+        }));
+
+        imp.webp_quality_row.connect_value_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_int("webp-quality", row.value() as i32);
+        }));
+
+        imp.progressive_resample_row.connect_active_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_boolean("progressive-resample", row.is_active());
+        }));
+
+        imp.simd_resize_row.connect_active_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_boolean("simd-resize", row.is_active());
+        }));
+
+        imp.threads_row.connect_value_notify(glib::clone!(@strong settings => move |row| {
+            let _ = settings.set_int("threads", row.value() as i32);
+        }));
+
+        imp.png_compression_combo.connect_selected_notify(glib::clone!(@strong settings => move |combo| {
+            if let Some(compression) = PngCompression::all().get(combo.selected() as usize) {
+                let _ = settings.set_string("png-compression", compression.id());
+            }
+        }));
+
+        imp.png_filter_combo.connect_selected_notify(glib::clone!(@strong settings => move |combo| {
+            if let Some(filter) = PngFilter::all().get(combo.selected() as usize) {
+                let _ = settings.set_string("png-filter", filter.id());
+            }
+        }));
+    }
+}
+
+/// Encoder settings saved in the Preferences window
+pub fn load_encoder_settings() -> EncoderSettings {
+    let settings = gio::Settings::new("codes.lsb.jair");
+    let defaults = EncoderSettings::default();
+
+    EncoderSettings {
+        jpeg_quality: u8::try_from(settings.int("jpeg-quality")).unwrap_or(defaults.jpeg_quality),
+        jpeg_progressive: settings.boolean("jpeg-progressive"),
+        png_compression: PngCompression::from_id(&settings.string("png-compression"))
+            .unwrap_or(defaults.png_compression),
+        png_filter: PngFilter::from_id(&settings.string("png-filter"))
+            .unwrap_or(defaults.png_filter),
+        webp_quality: u8::try_from(settings.int("webp-quality")).unwrap_or(defaults.webp_quality),
+    }
+}
+
+/// Resample strategy saved in the Preferences window
+pub fn load_resample_strategy() -> ResampleStrategy {
+    let settings = gio::Settings::new("codes.lsb.jair");
+    if settings.boolean("progressive-resample") {
+        ResampleStrategy::Progressive
+    } else {
+        ResampleStrategy::Direct
+    }
+}
+
+/// Resize backend saved in the Preferences window
+pub fn load_resize_backend() -> ResizeBackend {
+    let settings = gio::Settings::new("codes.lsb.jair");
+    if settings.boolean("simd-resize") {
+        ResizeBackend::Simd
+    } else {
+        ResizeBackend::Reference
+    }
+}
+
+/// Worker threads saved in the Preferences window, 0 for one per CPU core
+pub fn load_thread_count() -> usize {
+    let settings = gio::Settings::new("codes.lsb.jair");
+    usize::try_from(settings.int("threads")).unwrap_or(0)
+}
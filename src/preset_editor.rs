@@ -0,0 +1,473 @@
+/* MIT License
+ *
+ * Copyright (c) 2025 Leandro Santiago
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+use gtk::prelude::*;
+use adw::subclass::prelude::*;
+use adw::prelude::*;
+use gtk::{gio, glib};
+use gettextrs::gettext;
+
+use crate::models::user_preset::{PresetFile, PresetFileSize, UserPreset};
+use crate::models::{OutputFormat, ResizeMode};
+use crate::services::user_presets;
+
+/// Largest width or height offered by the size rows
+const MAX_DIMENSION: f64 = 16384.0;
+
+mod imp {
+    use super::*;
+
+    use glib::subclass::Signal;
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    /// Widgets of one size in the edit page
+    #[derive(Debug)]
+    pub struct SizeRow {
+        pub row: adw::ActionRow,
+        pub name: gtk::Entry,
+        pub width: gtk::SpinButton,
+        pub height: gtk::SpinButton,
+        /// Kept as loaded, the editor doesn't change per-size modes
+        pub mode: Option<ResizeMode>,
+    }
+
+    /// The preset open in the edit page
+    #[derive(Debug)]
+    pub struct EditState {
+        pub preset: UserPreset,
+        pub name_row: adw::EntryRow,
+        pub format_combo: adw::ComboRow,
+        pub sizes_group: adw::PreferencesGroup,
+        pub size_rows: Vec<SizeRow>,
+    }
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/codes/lsb/jair/preset_editor.ui")]
+    pub struct JairPresetEditor {
+        #[template_child]
+        pub presets_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub new_preset_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub empty_row: TemplateChild<adw::ActionRow>,
+
+        // Data
+        pub preset_rows: RefCell<Vec<adw::ActionRow>>,
+        pub editing: RefCell<Option<EditState>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for JairPresetEditor {
+        const NAME: &'static str = "JairPresetEditor";
+        type Type = super::JairPresetEditor;
+        type ParentType = adw::PreferencesWindow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for JairPresetEditor {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.setup_signals();
+            obj.refresh_list();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("presets-changed").build()])
+        }
+    }
+
+    impl WidgetImpl for JairPresetEditor {}
+    impl WindowImpl for JairPresetEditor {}
+    impl AdwWindowImpl for JairPresetEditor {}
+    impl PreferencesWindowImpl for JairPresetEditor {}
+}
+
+glib::wrapper! {
+    pub struct JairPresetEditor(ObjectSubclass<imp::JairPresetEditor>)
+        @extends gtk::Widget, gtk::Window, adw::Window, adw::PreferencesWindow,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JairPresetEditor {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Called after a preset is saved, duplicated or deleted
+    pub fn connect_presets_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_local("presets-changed", false, move |values| {
+            let editor = values[0].get::<Self>().expect("presets-changed is emitted by the editor");
+            f(&editor);
+            None
+        })
+    }
+
+    fn setup_signals(&self) {
+        self.imp().new_preset_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+            let preset = UserPreset {
+                id: String::new(),
+                name: String::new(),
+                sizes: Vec::new(),
+                output_format: None,
+                layout: None,
+            };
+            editor.edit_preset(preset);
+        }));
+    }
+
+    fn presets_changed(&self) {
+        self.refresh_list();
+        self.emit_by_name::<()>("presets-changed", &[]);
+    }
+
+    /// Show `error` in a toast, after `heading` such as "Could not save the preset"
+    fn show_error(&self, heading: &str, error: &anyhow::Error) {
+        let toast = adw::Toast::builder()
+            .title(format!("{}: {:#}", heading, error))
+            .timeout(5)
+            .build();
+        self.add_toast(toast);
+    }
+
+    fn refresh_list(&self) {
+        let imp = self.imp();
+
+        for row in imp.preset_rows.borrow_mut().drain(..) {
+            imp.presets_group.remove(&row);
+        }
+
+        let loaded = user_presets::load_all();
+        imp.empty_row.set_visible(loaded.presets.is_empty() && loaded.skipped.is_empty());
+
+        for preset in loaded.presets {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&preset.name).as_str())
+                .subtitle(gettextrs::ngettext("{} size", "{} sizes", preset.sizes.len() as u32)
+                    .replace("{}", &preset.sizes.len().to_string()))
+                .activatable(true)
+                .build();
+
+            let edit_btn = gtk::Button::builder()
+                .icon_name("document-edit-symbolic")
+                .tooltip_text(gettext("Edit"))
+                .valign(gtk::Align::Center)
+                .build();
+            edit_btn.add_css_class("flat");
+
+            let duplicate_btn = gtk::Button::builder()
+                .icon_name("edit-copy-symbolic")
+                .tooltip_text(gettext("Duplicate"))
+                .valign(gtk::Align::Center)
+                .build();
+            duplicate_btn.add_css_class("flat");
+
+            let delete_btn = gtk::Button::builder()
+                .icon_name("edit-delete-symbolic")
+                .tooltip_text(gettext("Delete"))
+                .valign(gtk::Align::Center)
+                .build();
+            delete_btn.add_css_class("flat");
+
+            let edited = preset.clone();
+            row.connect_activated(glib::clone!(@weak self as editor => move |_| {
+                editor.edit_preset(edited.clone());
+            }));
+            let edited = preset.clone();
+            edit_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+                editor.edit_preset(edited.clone());
+            }));
+            let duplicated = preset.clone();
+            duplicate_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+                editor.duplicate_preset(&duplicated);
+            }));
+            delete_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+                editor.confirm_delete(&preset);
+            }));
+
+            row.add_suffix(&edit_btn);
+            row.add_suffix(&duplicate_btn);
+            row.add_suffix(&delete_btn);
+            imp.presets_group.add(&row);
+            imp.preset_rows.borrow_mut().push(row);
+        }
+
+        // Files that could not be read stay listed so they can be fixed
+        for (path, error) in loaded.skipped {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&name).as_str())
+                .subtitle(glib::markup_escape_text(&error).as_str())
+                .tooltip_text(path.display().to_string())
+                .build();
+
+            let warning = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            warning.add_css_class("warning");
+            row.add_prefix(&warning);
+            imp.presets_group.add(&row);
+            imp.preset_rows.borrow_mut().push(row);
+        }
+    }
+
+    fn duplicate_preset(&self, preset: &UserPreset) {
+        let mut copy = preset.clone();
+        copy.name = gettext("{} (Copy)").replace("{}", &preset.name);
+        copy.id = user_presets::new_id(&copy.name);
+
+        match user_presets::save(&copy) {
+            Ok(_) => self.presets_changed(),
+            Err(e) => self.show_error(&gettext("Could not duplicate the preset"), &e),
+        }
+    }
+
+    fn confirm_delete(&self, preset: &UserPreset) {
+        let dialog = adw::MessageDialog::new(
+            Some(self),
+            Some(&gettext("Delete Preset?")),
+            Some(&gettext("“{}” will be removed from your presets folder.").replace("{}", &preset.name)),
+        );
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("delete", &gettext("Delete"));
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        let id = preset.id.clone();
+        dialog.connect_response(None, glib::clone!(@weak self as editor => move |_, response| {
+            if response != "delete" {
+                return;
+            }
+            match user_presets::delete(&id) {
+                Ok(()) => editor.presets_changed(),
+                Err(e) => editor.show_error(&gettext("Could not delete the preset"), &e),
+            }
+        }));
+
+        dialog.present();
+    }
+
+    /// Open `preset` in a subpage; presets without an id are new
+    fn edit_preset(&self, preset: UserPreset) {
+        let title = if preset.id.is_empty() {
+            gettext("New Preset")
+        } else {
+            gettext("Edit Preset")
+        };
+
+        let save_btn = gtk::Button::with_label(&gettext("Save"));
+        save_btn.add_css_class("suggested-action");
+        save_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+            editor.save_edited();
+        }));
+
+        let header = adw::HeaderBar::new();
+        header.pack_end(&save_btn);
+
+        // Name and format
+        let name_row = adw::EntryRow::builder()
+            .title(gettext("Name"))
+            .text(preset.name.as_str())
+            .build();
+
+        let formats = gtk::StringList::new(&[&gettext("Chosen in the main window")]);
+        for format in OutputFormat::all() {
+            formats.append(&gettext(format.name()));
+        }
+        let format_index = preset.output_format
+            .and_then(|current| OutputFormat::all().iter().position(|format| *format == current))
+            .map_or(0, |index| index + 1);
+        let format_combo = adw::ComboRow::builder()
+            .title(gettext("Output Format"))
+            .model(&formats)
+            .selected(format_index as u32)
+            .build();
+
+        let general_group = adw::PreferencesGroup::new();
+        general_group.add(&name_row);
+        general_group.add(&format_combo);
+
+        // Sizes
+        let add_size_btn = gtk::Button::builder()
+            .icon_name("list-add-symbolic")
+            .tooltip_text(gettext("Add Size"))
+            .valign(gtk::Align::Center)
+            .build();
+        add_size_btn.add_css_class("flat");
+        add_size_btn.connect_clicked(glib::clone!(@weak self as editor => move |_| {
+            editor.add_size_row("", 0, 0, None);
+        }));
+
+        let sizes_group = adw::PreferencesGroup::builder()
+            .title(gettext("Sizes"))
+            .description(gettext("Leave width or height at 0 to keep the aspect ratio"))
+            .header_suffix(&add_size_btn)
+            .build();
+
+        let page = adw::PreferencesPage::new();
+        page.add(&general_group);
+        page.add(&sizes_group);
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&page));
+
+        let sizes = preset.sizes.clone();
+        self.imp().editing.replace(Some(imp::EditState {
+            preset,
+            name_row,
+            format_combo,
+            sizes_group,
+            size_rows: Vec::new(),
+        }));
+
+        for size in &sizes {
+            self.add_size_row(&size.name, size.width, size.height, size.mode);
+        }
+        if sizes.is_empty() {
+            self.add_size_row("", 0, 0, None);
+        }
+
+        let subpage = adw::NavigationPage::builder()
+            .title(title)
+            .child(&toolbar)
+            .build();
+        self.push_subpage(&subpage);
+    }
+
+    fn add_size_row(&self, name: &str, width: u32, height: u32, mode: Option<ResizeMode>) {
+        let mut editing = self.imp().editing.borrow_mut();
+        let Some(state) = editing.as_mut() else {
+            return;
+        };
+
+        let name_entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Name"))
+            .text(name)
+            .hexpand(true)
+            .valign(gtk::Align::Center)
+            .build();
+
+        let dimension = |value: u32, tooltip: String| {
+            let spin = gtk::SpinButton::with_range(0.0, MAX_DIMENSION, 1.0);
+            spin.set_value(f64::from(value));
+            spin.set_valign(gtk::Align::Center);
+            spin.set_tooltip_text(Some(&tooltip));
+            spin
+        };
+        let width_spin = dimension(width, gettext("Width"));
+        let height_spin = dimension(height, gettext("Height"));
+
+        let remove_btn = gtk::Button::builder()
+            .icon_name("edit-delete-symbolic")
+            .tooltip_text(gettext("Remove Size"))
+            .valign(gtk::Align::Center)
+            .build();
+        remove_btn.add_css_class("flat");
+
+        let row = adw::ActionRow::new();
+        row.add_prefix(&name_entry);
+        row.add_suffix(&width_spin);
+        row.add_suffix(&gtk::Label::new(Some("×")));
+        row.add_suffix(&height_spin);
+        row.add_suffix(&remove_btn);
+
+        remove_btn.connect_clicked(glib::clone!(@weak self as editor, @weak row => move |_| {
+            let mut editing = editor.imp().editing.borrow_mut();
+            if let Some(state) = editing.as_mut() {
+                state.size_rows.retain(|size_row| size_row.row != row);
+                state.sizes_group.remove(&row);
+            }
+        }));
+
+        state.sizes_group.add(&row);
+        state.size_rows.push(imp::SizeRow {
+            row,
+            name: name_entry,
+            width: width_spin,
+            height: height_spin,
+            mode,
+        });
+    }
+
+    fn save_edited(&self) {
+        let result = {
+            let editing = self.imp().editing.borrow();
+            let Some(state) = editing.as_ref() else {
+                return;
+            };
+
+            // The first entry leaves the format to the main window
+            let format = (state.format_combo.selected() as usize)
+                .checked_sub(1)
+                .and_then(|index| OutputFormat::all().get(index).copied())
+                .map(|format| format.id().to_string());
+
+            let file = PresetFile {
+                name: state.name_row.text().trim().to_string(),
+                format,
+                layout: state.preset.layout.as_ref().map(|layout| layout.id().to_string()),
+                sizes: state
+                    .size_rows
+                    .iter()
+                    .map(|size_row| PresetFileSize {
+                        name: size_row.name.text().trim().to_string(),
+                        width: size_row.width.value_as_int().max(0) as u32,
+                        height: size_row.height.value_as_int().max(0) as u32,
+                        mode: size_row.mode.map(|mode| mode.id().to_string()),
+                    })
+                    .collect(),
+            };
+
+            let id = if state.preset.id.is_empty() {
+                user_presets::new_id(&file.name)
+            } else {
+                state.preset.id.clone()
+            };
+
+            UserPreset::from_file(&id, file)
+                .map_err(anyhow::Error::msg)
+                .and_then(|preset| user_presets::save(&preset))
+        };
+
+        match result {
+            Ok(_) => {
+                self.imp().editing.replace(None);
+                self.pop_subpage();
+                self.presets_changed();
+            }
+            Err(e) => self.show_error(&gettext("Could not save the preset"), &e),
+        }
+    }
+}
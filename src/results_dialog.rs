@@ -0,0 +1,160 @@
+/* MIT License
+ *
+ * Copyright (c) 2025 Leandro Santiago
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+use gtk::prelude::*;
+use adw::subclass::prelude::*;
+use adw::prelude::*;
+use gtk::{gio, glib};
+use gettextrs::gettext;
+use std::path::Path;
+
+use crate::services::processor::ImageFailure;
+
+mod imp {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/codes/lsb/jair/results_dialog.ui")]
+    pub struct JairResultsDialog {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub copy_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub failures_group: TemplateChild<adw::PreferencesGroup>,
+
+        // Data
+        pub failures: RefCell<Vec<ImageFailure>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for JairResultsDialog {
+        const NAME: &'static str = "JairResultsDialog";
+        type Type = super::JairResultsDialog;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for JairResultsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.copy_btn.connect_clicked(glib::clone!(@weak self as imp => move |_| {
+                imp.obj().copy_to_clipboard();
+            }));
+        }
+    }
+
+    impl WidgetImpl for JairResultsDialog {}
+    impl WindowImpl for JairResultsDialog {}
+    impl AdwWindowImpl for JairResultsDialog {}
+}
+
+glib::wrapper! {
+    pub struct JairResultsDialog(ObjectSubclass<imp::JairResultsDialog>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JairResultsDialog {
+    /// One expandable row per image, listing what went wrong with it
+    pub fn new(failures: &[ImageFailure]) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+        dialog.imp().failures.replace(failures.to_vec());
+
+        let mut inputs: Vec<&Path> = Vec::new();
+        for failure in failures {
+            if !inputs.contains(&failure.input.as_path()) {
+                inputs.push(&failure.input);
+            }
+        }
+
+        for input in inputs {
+            let name = input.file_name().unwrap_or_default().to_string_lossy();
+            let row = adw::ExpanderRow::builder()
+                .title(glib::markup_escape_text(&name).as_str())
+                .build();
+            if let Some(parent) = input.parent() {
+                row.set_subtitle(&glib::markup_escape_text(&parent.display().to_string()));
+            }
+
+            for failure in failures.iter().filter(|failure| failure.input == input) {
+                let size = match &failure.size {
+                    Some(size) => format!("{} {}", gettext("Size"), size),
+                    None => gettext("Whole image"),
+                };
+                let message = match &failure.hint {
+                    Some(hint) => format!("{}\n{}", failure.message, hint),
+                    None => failure.message.clone(),
+                };
+                let detail = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(&size).as_str())
+                    .subtitle(glib::markup_escape_text(&message).as_str())
+                    .subtitle_selectable(true)
+                    .build();
+                row.add_row(&detail);
+            }
+
+            row.set_expanded(failures.len() == 1);
+            dialog.imp().failures_group.add(&row);
+        }
+
+        dialog
+    }
+
+    /// The failures as plain text, one per line
+    fn report_text(&self) -> String {
+        self.imp()
+            .failures
+            .borrow()
+            .iter()
+            .map(|failure| match &failure.size {
+                Some(size) => format!("{} [{}]: {}", failure.input.display(), size, failure.message),
+                None => format!("{}: {}", failure.input.display(), failure.message),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn copy_to_clipboard(&self) {
+        self.clipboard().set_text(&self.report_text());
+
+        let toast = adw::Toast::builder()
+            .title(gettext("Copied to clipboard"))
+            .timeout(2)
+            .build();
+        self.imp().toast_overlay.add_toast(toast);
+    }
+}
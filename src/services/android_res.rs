@@ -0,0 +1,236 @@
+use crate::models::adaptive_icon::{
+    hex_color, AdaptiveBackground, AdaptiveIconOptions, MonochromeLayer, LAYER_DP, SAFE_ZONE_DP,
+};
+use crate::models::android_sizes::{mipmap_entry, MipmapEntry};
+use crate::models::{is_valid_resource_name, OutputFormat, ResizeMode, Size};
+use crate::services::processor::{self, SourceImage, ProcessError, ProcessOptions, Result};
+use crate::services::resampler::{resampler, Resampler};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use std::path::{Path, PathBuf};
+
+pub const RES_DIR: &str = "res";
+pub const ANYDPI_DIR: &str = "mipmap-anydpi-v26";
+
+/// Write launcher icons into an Android `res/mipmap-*` tree, e.g.
+/// `res/mipmap-xxhdpi/ic_launcher.png`. Icons are always PNG; sizes without a
+/// mipmap location (store graphics, screenshots) fall back to one folder per size.
+///
+/// For adaptive sizes the input is the foreground layer: it is padded to the
+/// 66dp safe zone and written with its background (and optional monochrome)
+/// layer, followed by the `mipmap-anydpi-v26` XML descriptors.
+pub fn save(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+    resource_name: &str,
+    adaptive: &AdaptiveIconOptions,
+) -> Result<Vec<PathBuf>> {
+    if !is_valid_resource_name(resource_name) {
+        return Err(ProcessError::InvalidResourceName { name: resource_name.to_string() });
+    }
+
+    let res_dir = out_dir.join(RES_DIR);
+    let mut saved = Vec::new();
+    let mut others = Vec::new();
+    let mut layers: Option<AdaptiveLayers> = None;
+    let resampler = resampler(options.resize_backend);
+
+    for s in sizes {
+        options.check_cancelled()?;
+        let Some(entry) = mipmap_entry(&s.name) else {
+            others.push(s.clone());
+            continue;
+        };
+
+        if entry.layer.is_none() {
+            let out_path = mipmap_path(&res_dir, &entry, &entry.file_stem(resource_name))?;
+            let resized = img.resize_to(s, options.resize_mode, options.crop_hint(input));
+            let bytes = processor::save_image(&resized, &out_path, OutputFormat::Png, &options.encoder)
+                .map_err(processor::in_size(s))?;
+            options.report_output(input, s, bytes);
+            saved.push(out_path);
+            continue;
+        }
+
+        if layers.is_none() {
+            layers = Some(AdaptiveLayers::load(img.image(), adaptive)?);
+        }
+        let layers = layers.as_ref().expect("adaptive layers loaded above");
+
+        let foreground = mipmap_path(&res_dir, &entry, &format!("{}_foreground", resource_name))?;
+        let layer = safe_zone_layer(layers.foreground, s.width, resampler);
+        let mut bytes = processor::save_image(&layer, &foreground, OutputFormat::Png, &options.encoder)
+            .map_err(processor::in_size(s))?;
+        saved.push(foreground);
+
+        if let Some(background_img) = &layers.background {
+            let background = mipmap_path(&res_dir, &entry, &format!("{}_background", resource_name))?;
+            let square = Size::new(s.name.clone(), s.width, s.width);
+            let layer = processor::resize_to(background_img, &square, ResizeMode::Cover, None, resampler);
+            bytes += processor::save_image(&layer, &background, OutputFormat::Png, &options.encoder)
+                .map_err(processor::in_size(s))?;
+            saved.push(background);
+        }
+
+        if let Some(monochrome_img) = &layers.monochrome {
+            let monochrome = mipmap_path(&res_dir, &entry, &format!("{}_monochrome", resource_name))?;
+            let layer = alpha_mask(&safe_zone_layer(monochrome_img, s.width, resampler));
+            bytes += processor::save_image(&layer, &monochrome, OutputFormat::Png, &options.encoder)
+                .map_err(processor::in_size(s))?;
+            saved.push(monochrome);
+        }
+
+        options.report_output(input, s, bytes);
+    }
+
+    if layers.is_some() {
+        saved.extend(write_descriptors(&res_dir, resource_name, adaptive)?);
+    }
+
+    if !others.is_empty() {
+        saved.extend(processor::save_in_size_folders(img, input, out_dir, &others, options)?);
+    }
+
+    Ok(saved)
+}
+
+/// Source images for the adaptive layers, decoded once per input
+struct AdaptiveLayers<'a> {
+    foreground: &'a DynamicImage,
+    background: Option<DynamicImage>,
+    monochrome: Option<DynamicImage>,
+}
+
+impl<'a> AdaptiveLayers<'a> {
+    fn load(foreground: &'a DynamicImage, adaptive: &AdaptiveIconOptions) -> Result<Self> {
+        let background = match &adaptive.background {
+            AdaptiveBackground::Color(_) => None,
+            AdaptiveBackground::Image(path) => Some(processor::open_image(path)?),
+        };
+
+        let monochrome = match &adaptive.monochrome {
+            None => None,
+            Some(MonochromeLayer::FromForeground) => Some(foreground.clone()),
+            Some(MonochromeLayer::Image(path)) => Some(processor::open_image(path)?),
+        };
+
+        Ok(AdaptiveLayers { foreground, background, monochrome })
+    }
+}
+
+fn mipmap_path(res_dir: &Path, entry: &MipmapEntry, file_stem: &str) -> Result<PathBuf> {
+    let folder = res_dir.join(entry.folder());
+    processor::create_dir_all(&folder)?;
+    Ok(folder.join(format!("{}.png", file_stem)))
+}
+
+/// Scale `img` into the 66dp safe zone, centred on a transparent 108dp layer
+fn safe_zone_layer(img: &DynamicImage, layer_px: u32, resampler: &dyn Resampler) -> DynamicImage {
+    let safe_px = (layer_px * SAFE_ZONE_DP / LAYER_DP).max(1);
+    let safe_zone = Size::new("safe zone", safe_px, safe_px);
+    let content = processor::resize_to(img, &safe_zone, ResizeMode::Fit, None, resampler).to_rgba8();
+
+    let mut layer = RgbaImage::new(layer_px, layer_px);
+    let x = (layer_px - content.width()) / 2;
+    let y = (layer_px - content.height()) / 2;
+    imageops::overlay(&mut layer, &content, i64::from(x), i64::from(y));
+
+    DynamicImage::ImageRgba8(layer)
+}
+
+/// Launchers tint the monochrome layer themselves, so keep only its shape
+fn alpha_mask(img: &DynamicImage) -> DynamicImage {
+    let mut mask = img.to_rgba8();
+    for pixel in mask.pixels_mut() {
+        *pixel = Rgba([0xFF, 0xFF, 0xFF, pixel[3]]);
+    }
+    DynamicImage::ImageRgba8(mask)
+}
+
+/// Write `mipmap-anydpi-v26/<name>.xml`, `<name>_round.xml` and, for colour
+/// backgrounds, the `values/<name>_background.xml` colour resource
+fn write_descriptors(
+    res_dir: &Path,
+    resource_name: &str,
+    adaptive: &AdaptiveIconOptions,
+) -> Result<Vec<PathBuf>> {
+    let mut saved = Vec::new();
+
+    let background = match &adaptive.background {
+        AdaptiveBackground::Color(color) => {
+            let values_dir = res_dir.join("values");
+            processor::create_dir_all(&values_dir)?;
+
+            let path = values_dir.join(format!("{}_background.xml", resource_name));
+            let xml = format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n    <color name=\"{}_background\">{}</color>\n</resources>\n",
+                resource_name,
+                hex_color(*color)
+            );
+            processor::write_file(&path, xml)?;
+            saved.push(path);
+
+            format!("@color/{}_background", resource_name)
+        }
+        AdaptiveBackground::Image(_) => format!("@mipmap/{}_background", resource_name),
+    };
+
+    let xml = adaptive_icon_xml(resource_name, &background, adaptive.monochrome.is_some());
+
+    let anydpi_dir = res_dir.join(ANYDPI_DIR);
+    processor::create_dir_all(&anydpi_dir)?;
+
+    for file_stem in [resource_name.to_string(), format!("{}_round", resource_name)] {
+        let path = anydpi_dir.join(format!("{}.xml", file_stem));
+        processor::write_file(&path, &xml)?;
+        saved.push(path);
+    }
+
+    Ok(saved)
+}
+
+fn adaptive_icon_xml(resource_name: &str, background: &str, monochrome: bool) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<adaptive-icon xmlns:android=\"http://schemas.android.com/apk/res/android\">\n",
+    );
+    xml.push_str(&format!("    <background android:drawable=\"{}\" />\n", background));
+    xml.push_str(&format!("    <foreground android:drawable=\"@mipmap/{}_foreground\" />\n", resource_name));
+    if monochrome {
+        xml.push_str(&format!("    <monochrome android:drawable=\"@mipmap/{}_monochrome\" />\n", resource_name));
+    }
+    xml.push_str("</adaptive-icon>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adaptive_icon_xml, safe_zone_layer};
+    use crate::services::resampler::ReferenceResampler;
+    use image::{DynamicImage, GenericImageView, RgbaImage, Rgba};
+
+    #[test]
+    fn pads_foreground_to_the_safe_zone() {
+        let opaque = DynamicImage::ImageRgba8(RgbaImage::from_pixel(400, 400, Rgba([255, 0, 0, 255])));
+        let layer = safe_zone_layer(&opaque, 108, &ReferenceResampler);
+
+        assert_eq!(layer.dimensions(), (108, 108));
+        assert_eq!(layer.get_pixel(0, 0)[3], 0);
+        assert_eq!(layer.get_pixel(20, 20)[3], 0);
+        assert_eq!(layer.get_pixel(54, 54)[3], 255);
+        assert_eq!(layer.get_pixel(22, 54)[3], 255);
+    }
+
+    #[test]
+    fn writes_adaptive_icon_descriptor() {
+        let xml = adaptive_icon_xml("ic_launcher", "@color/ic_launcher_background", true);
+
+        assert!(xml.contains("<background android:drawable=\"@color/ic_launcher_background\" />"));
+        assert!(xml.contains("<foreground android:drawable=\"@mipmap/ic_launcher_foreground\" />"));
+        assert!(xml.contains("<monochrome android:drawable=\"@mipmap/ic_launcher_monochrome\" />"));
+
+        let without_monochrome = adaptive_icon_xml("ic_launcher", "@color/ic_launcher_background", false);
+        assert!(!without_monochrome.contains("monochrome"));
+    }
+}
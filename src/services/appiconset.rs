@@ -0,0 +1,137 @@
+use crate::models::ios_sizes::{app_icon_entry, AppIconEntry};
+use crate::models::{OutputFormat, Size};
+use crate::services::processor::{self, SourceImage, ProcessOptions, Result};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub const APP_ICON_SET_DIR: &str = "AppIcon.appiconset";
+
+/// Write an Xcode `AppIcon.appiconset` with its `Contents.json`.
+/// Icons are always PNG as required by Xcode; sizes without a catalog entry
+/// (screenshots, custom sizes) fall back to one folder per size.
+pub fn save(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    let (icons, others): (Vec<&Size>, Vec<&Size>) = sizes
+        .iter()
+        .partition(|s| app_icon_entry(&s.name).is_some());
+
+    let mut saved = Vec::new();
+
+    if !icons.is_empty() {
+        let folder = out_dir.join(APP_ICON_SET_DIR);
+        processor::create_dir_all(&folder)?;
+
+        let written = icons
+            .par_iter()
+            .map(|s| {
+                options.check_cancelled()?;
+                let entry = app_icon_entry(&s.name).expect("partitioned by catalog entry");
+                let filename = format!("{}.png", s.name);
+                let out_path = folder.join(&filename);
+
+                let mut resized = img.resize_to(s, options.resize_mode, options.crop_hint(input));
+                if entry.is_marketing() {
+                    resized = flatten(&resized);
+                }
+                let bytes = processor::save_image(&resized, &out_path, OutputFormat::Png, &options.encoder)
+                    .map_err(processor::in_size(s))?;
+                options.report_output(input, s, bytes);
+
+                Ok(((entry, filename), out_path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (images, paths): (Vec<_>, Vec<_>) = written.into_iter().unzip();
+        saved.extend(paths);
+
+        let contents_path = folder.join("Contents.json");
+        processor::write_file(&contents_path, contents_json(&images))?;
+        saved.push(contents_path);
+    }
+
+    if !others.is_empty() {
+        let others: Vec<Size> = others.into_iter().cloned().collect();
+        saved.extend(processor::save_in_size_folders(img, input, out_dir, &others, options)?);
+    }
+
+    Ok(saved)
+}
+
+/// The App Store icon may not have an alpha channel, so transparent pixels
+/// are blended onto white rather than dropped to black
+fn flatten(img: &DynamicImage) -> DynamicImage {
+    let mut canvas = RgbaImage::from_pixel(img.width(), img.height(), Rgba([255, 255, 255, 255]));
+    imageops::overlay(&mut canvas, &img.to_rgba8(), 0, 0);
+    DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8())
+}
+
+/// Build `Contents.json` in the same shape Xcode writes it
+fn contents_json(images: &[(AppIconEntry, String)]) -> String {
+    let entries: Vec<String> = images
+        .iter()
+        .map(|(entry, filename)| {
+            let mut fields = vec![
+                ("filename", filename.as_str()),
+                ("idiom", entry.idiom),
+            ];
+            if let Some(role) = entry.role {
+                fields.push(("role", role));
+            }
+            fields.push(("scale", entry.scale));
+            fields.push(("size", entry.size));
+            if let Some(subtype) = entry.subtype {
+                fields.push(("subtype", subtype));
+            }
+
+            let body: Vec<String> = fields
+                .iter()
+                .map(|(key, value)| format!("      \"{}\" : \"{}\"", key, value))
+                .collect();
+            format!("    {{\n{}\n    }}", body.join(",\n"))
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"images\" : [\n{}\n  ],\n  \"info\" : {{\n    \"author\" : \"jair\",\n    \"version\" : 1\n  }}\n}}\n",
+        entries.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contents_json, flatten};
+    use crate::models::ios_sizes::app_icon_entry;
+    use image::{DynamicImage, Rgb, Rgba, RgbaImage};
+
+    #[test]
+    fn marketing_icon_is_flattened_onto_white() {
+        let mut icon = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 0, 0]));
+        icon.put_pixel(1, 0, Rgba([255, 0, 0, 128]));
+
+        let flat = flatten(&DynamicImage::ImageRgba8(icon)).to_rgb8();
+        assert_eq!(flat.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(flat.get_pixel(1, 0), &Rgb([255, 127, 127]));
+    }
+
+    #[test]
+    fn writes_xcode_contents_json() {
+        let images = vec![
+            (app_icon_entry("iphone-60pt-3x").unwrap(), "iphone-60pt-3x.png".to_string()),
+            (app_icon_entry("watch-44pt-2x-home").unwrap(), "watch-44pt-2x-home.png".to_string()),
+        ];
+
+        let json = contents_json(&images);
+
+        assert!(json.contains(
+            "      \"filename\" : \"iphone-60pt-3x.png\",\n      \"idiom\" : \"iphone\",\n      \"scale\" : \"3x\",\n      \"size\" : \"60x60\"\n"
+        ));
+        assert!(json.contains("\"role\" : \"appLauncher\""));
+        assert!(json.contains("\"subtype\" : \"40mm\""));
+        assert!(json.trim_end().ends_with("\"version\" : 1\n  }\n}"));
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::{Context, Result};
+use gettextrs::gettext;
+use crate::services::processor;
+use glob::{MatchOptions, Pattern};
+use std::path::{Path, PathBuf};
+
+/// How a folder is searched for images
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Also look inside subfolders
+    pub recursive: bool,
+    /// Subfolder levels to descend into when recursive, `None` for no limit
+    pub max_depth: Option<usize>,
+    /// An image must match one of these to be added, any image if empty
+    pub include: Vec<Pattern>,
+    /// Images matching any of these are skipped
+    pub exclude: Vec<Pattern>,
+}
+
+/// An image found while scanning a folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedImage {
+    pub path: PathBuf,
+    /// Folder of the image relative to the scanned one, empty at the top level
+    pub subfolder: PathBuf,
+}
+
+/// Images found while scanning a folder, and the subfolders that could not
+/// be read
+#[derive(Debug, Default)]
+pub struct ScanResult {
+    pub images: Vec<ScannedImage>,
+    /// Each subfolder that was skipped, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Parse comma separated glob patterns such as `*.png, icons/**`.
+/// On failure returns the first invalid pattern.
+pub fn parse_patterns(text: &str) -> Result<Vec<Pattern>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| Pattern::new(pattern).map_err(|_| pattern.to_string()))
+        .collect()
+}
+
+/// Find supported images in `dir`, sorted by path. Hidden subfolders are
+/// skipped, and subfolders that can't be read are listed in `skipped`.
+pub fn scan_directory(dir: &Path, options: &ScanOptions) -> Result<ScanResult> {
+    if !dir.is_dir() {
+        return Err(anyhow::anyhow!("{} {}", gettext("Not a folder:"), dir.display()));
+    }
+
+    let mut result = ScanResult::default();
+    scan_into(dir, Path::new(""), 0, options, &mut result)?;
+    result.images.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(result)
+}
+
+fn scan_into(
+    dir: &Path,
+    subfolder: &Path,
+    depth: usize,
+    options: &ScanOptions,
+    result: &mut ScanResult,
+) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("{} {}", gettext("Could not read:"), dir.display()))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let relative = subfolder.join(&name);
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+        if is_dir {
+            let within_depth = !matches!(options.max_depth, Some(max) if depth >= max);
+            if options.recursive && within_depth && !name.to_string_lossy().starts_with('.') {
+                if let Err(e) = scan_into(&path, &relative, depth + 1, options, result) {
+                    let reason = e.root_cause().to_string();
+                    result.skipped.push((path, reason));
+                }
+            }
+        } else if path.is_file() && processor::is_supported_image(&path) && matches_filters(&relative, options) {
+            result.images.push(ScannedImage {
+                path,
+                subfolder: subfolder.to_path_buf(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Patterns containing a `/` are matched against the path relative to the
+/// scanned folder, others against the file name alone. Case is ignored.
+fn matches_filters(relative: &Path, options: &ScanOptions) -> bool {
+    let match_options = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+    let file_name = Path::new(relative.file_name().unwrap_or_default());
+
+    let matches = |pattern: &Pattern| {
+        let text = if pattern.as_str().contains('/') { relative } else { file_name };
+        pattern.matches_path_with(text, match_options)
+    };
+
+    (options.include.is_empty() || options.include.iter().any(matches))
+        && !options.exclude.iter().any(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_patterns, scan_directory, ScanOptions};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn scans_subfolders_with_filters_and_depth() {
+        let dir = std::env::temp_dir().join(format!("jair-scan-{}", std::process::id()));
+        for folder in ["icons/old", "photos", ".cache"] {
+            std::fs::create_dir_all(dir.join(folder)).unwrap();
+        }
+        for file in [
+            "logo.png",
+            "notes.txt",
+            "icons/app.PNG",
+            "icons/draft-app.png",
+            "icons/old/app.png",
+            "photos/beach.jpg",
+            ".cache/thumb.png",
+        ] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+
+        let found = |options: &ScanOptions| -> Vec<PathBuf> {
+            scan_directory(&dir, options)
+                .unwrap()
+                .images
+                .into_iter()
+                .map(|image| image.path.strip_prefix(&dir).unwrap().to_path_buf())
+                .collect()
+        };
+
+        let flat = found(&ScanOptions::default());
+        assert_eq!(flat, [Path::new("logo.png")]);
+
+        let mut options = ScanOptions { recursive: true, ..ScanOptions::default() };
+        assert_eq!(found(&options).len(), 5);
+
+        options.max_depth = Some(1);
+        options.include = parse_patterns("*.png").unwrap();
+        options.exclude = parse_patterns("draft-*, photos/*").unwrap();
+        assert_eq!(found(&options), [Path::new("icons/app.PNG"), Path::new("logo.png")]);
+
+        let scanned = scan_directory(&dir, &options).unwrap();
+        assert!(scanned.skipped.is_empty());
+        let scanned = scanned.images;
+        assert_eq!(scanned[0].subfolder, Path::new("icons"));
+        assert_eq!(scanned[1].subfolder, Path::new(""));
+
+        assert_eq!(parse_patterns("*.png, [").unwrap_err(), "[");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
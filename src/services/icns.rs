@@ -0,0 +1,152 @@
+use crate::models::ios_sizes::{mac_icon_entry, MacIconEntry};
+use crate::models::{OutputFormat, Size};
+use crate::services::processor::{self, SourceImage, ProcessError, ProcessOptions, Result};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub const ICON_SET_DIR: &str = "AppIcon.iconset";
+
+/// Write an `AppIcon.iconset` folder with the `icon_16x16@2x.png` naming
+/// `iconutil` expects. Sizes that are not macOS icons fall back to one folder per size.
+pub fn save_iconset(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    let (icons, others) = split_mac_icons(sizes);
+    let mut saved = Vec::new();
+
+    if !icons.is_empty() {
+        let folder = out_dir.join(ICON_SET_DIR);
+        processor::create_dir_all(&folder)?;
+
+        let written = icons
+            .par_iter()
+            .map(|(s, entry)| {
+                options.check_cancelled()?;
+                let out_path = folder.join(entry.filename);
+                let resized = img.resize_to(s, options.resize_mode, options.crop_hint(input));
+                let bytes = processor::save_image(&resized, &out_path, OutputFormat::Png, &options.encoder)
+                    .map_err(processor::in_size(s))?;
+                options.report_output(input, s, bytes);
+                Ok(out_path)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        saved.extend(written);
+    }
+
+    saved.extend(save_others(img, input, out_dir, &others, options)?);
+    Ok(saved)
+}
+
+/// Write a native `<name>.icns` without needing `iconutil`.
+/// Sizes that are not macOS icons fall back to one folder per size.
+pub fn save_icns(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    let (icons, others) = split_mac_icons(sizes);
+    let mut saved = Vec::new();
+
+    if !icons.is_empty() {
+        processor::create_dir_all(out_dir)?;
+
+        let filename = input.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("image");
+        let out_path = out_dir.join(format!("{}.icns", filename));
+
+        let elements = icons
+            .par_iter()
+            .map(|(s, entry)| {
+                options.check_cancelled()?;
+                let mut png = Vec::new();
+                img.resize_to(s, options.resize_mode, options.crop_hint(input))
+                    .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+                    .map_err(|source| ProcessError::Encode { path: out_path.clone(), source: source.into() })
+                    .map_err(processor::in_size(s))?;
+                Ok((entry.icns_type, png))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let icns = encode(&elements);
+        processor::write_file(&out_path, &icns)?;
+        for (index, (s, _)) in icons.iter().enumerate() {
+            options.report_output(input, s, if index == 0 { icns.len() as u64 } else { 0 });
+        }
+        saved.push(out_path);
+    }
+
+    saved.extend(save_others(img, input, out_dir, &others, options)?);
+    Ok(saved)
+}
+
+/// Encode PNG elements into an ICNS container, all lengths are big-endian
+/// and include their 8-byte headers
+pub fn encode(elements: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let total = 8 + elements.iter().map(|(_, data)| 8 + data.len()).sum::<usize>();
+    let mut icns = Vec::with_capacity(total);
+
+    icns.extend_from_slice(b"icns");
+    icns.extend_from_slice(&(total as u32).to_be_bytes());
+
+    for (icns_type, data) in elements {
+        icns.extend_from_slice(icns_type);
+        icns.extend_from_slice(&((8 + data.len()) as u32).to_be_bytes());
+        icns.extend_from_slice(data);
+    }
+
+    icns
+}
+
+fn split_mac_icons(sizes: &[Size]) -> (Vec<(&Size, MacIconEntry)>, Vec<Size>) {
+    let mut icons = Vec::new();
+    let mut others = Vec::new();
+
+    for s in sizes {
+        match mac_icon_entry(&s.name) {
+            Some(entry) => icons.push((s, entry)),
+            None => others.push(s.clone()),
+        }
+    }
+
+    (icons, others)
+}
+
+fn save_others(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    others: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    if others.is_empty() {
+        return Ok(Vec::new());
+    }
+    processor::save_in_size_folders(img, input, out_dir, others, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+
+    #[test]
+    fn writes_icns_header_and_elements() {
+        let icns = encode(&[(*b"icp4", vec![1, 2, 3]), (*b"ic10", vec![4, 5])]);
+
+        assert_eq!(&icns[0..4], b"icns");
+        assert_eq!(u32::from_be_bytes(icns[4..8].try_into().unwrap()) as usize, icns.len());
+
+        assert_eq!(&icns[8..12], b"icp4");
+        assert_eq!(u32::from_be_bytes(icns[12..16].try_into().unwrap()), 11);
+        assert_eq!(&icns[16..19], &[1, 2, 3]);
+
+        assert_eq!(&icns[19..23], b"ic10");
+        assert_eq!(u32::from_be_bytes(icns[23..27].try_into().unwrap()), 10);
+    }
+}
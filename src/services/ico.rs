@@ -0,0 +1,192 @@
+use crate::models::Size;
+use crate::services::processor::{self, SourceImage, ProcessError, ProcessOptions, Result};
+use image::{DynamicImage, GenericImageView};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Largest dimension an ICO directory entry can describe
+pub const MAX_ICO_SIZE: u32 = 256;
+
+/// Entries at least this large are stored PNG-compressed, smaller ones as BMP
+const PNG_ENTRY_SIZE: u32 = 256;
+
+/// Pack every square size up to 256px into a single `<name>.ico`, e.g.
+/// `favicon.ico` for `favicon.png`. Other sizes fall back to one folder per size.
+pub fn save(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    let (entries, others): (Vec<&Size>, Vec<&Size>) = sizes
+        .iter()
+        .partition(|s| s.width == s.height && s.width > 0 && s.width <= MAX_ICO_SIZE);
+
+    let mut saved = Vec::new();
+
+    if !entries.is_empty() {
+        processor::create_dir_all(out_dir)?;
+
+        let mut frames = entries
+            .par_iter()
+            .map(|s| {
+                options.check_cancelled()?;
+                Ok(img.resize_to(s, options.resize_mode, options.crop_hint(input)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        frames.sort_by_key(|frame| frame.width());
+        frames.dedup_by_key(|frame| frame.dimensions());
+
+        let filename = input.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("image");
+        let out_path = out_dir.join(format!("{}.ico", filename));
+
+        let ico = encode(&frames)
+            .map_err(|source| ProcessError::Encode { path: out_path.clone(), source: source.into() })?;
+        processor::write_file(&out_path, &ico)?;
+        for (index, s) in entries.iter().enumerate() {
+            options.report_output(input, s, if index == 0 { ico.len() as u64 } else { 0 });
+        }
+        saved.push(out_path);
+    }
+
+    if !others.is_empty() {
+        let others: Vec<Size> = others.into_iter().cloned().collect();
+        saved.extend(processor::save_in_size_folders(img, input, out_dir, &others, options)?);
+    }
+
+    Ok(saved)
+}
+
+/// Encode frames into an ICO container
+pub fn encode(frames: &[DynamicImage]) -> image::ImageResult<Vec<u8>> {
+    let images: Vec<Vec<u8>> = frames
+        .iter()
+        .map(|frame| {
+            if frame.width() >= PNG_ENTRY_SIZE {
+                let mut png = Vec::new();
+                frame.write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)?;
+                Ok(png)
+            } else {
+                Ok(bmp_entry(frame))
+            }
+        })
+        .collect::<image::ImageResult<_>>()?;
+
+    let header_len = 6 + 16 * frames.len();
+    let mut ico = Vec::with_capacity(header_len + images.iter().map(Vec::len).sum::<usize>());
+
+    // ICONDIR
+    ico.extend_from_slice(&0u16.to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&(frames.len() as u16).to_le_bytes());
+
+    // ICONDIRENTRY per frame, 0 means 256
+    let mut offset = header_len as u32;
+    for (frame, data) in frames.iter().zip(&images) {
+        ico.push((frame.width() % 256) as u8);
+        ico.push((frame.height() % 256) as u8);
+        ico.push(0);
+        ico.push(0);
+        ico.extend_from_slice(&1u16.to_le_bytes());
+        ico.extend_from_slice(&32u16.to_le_bytes());
+        ico.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&offset.to_le_bytes());
+        offset += data.len() as u32;
+    }
+
+    for data in images {
+        ico.extend_from_slice(&data);
+    }
+
+    Ok(ico)
+}
+
+/// 32-bit BGRA DIB with the AND mask ICO expects after the pixel data
+fn bmp_entry(frame: &DynamicImage) -> Vec<u8> {
+    let rgba = frame.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let mask_stride = width.div_ceil(32) * 4;
+    let xor_len = width * height * 4;
+    let and_len = mask_stride * height;
+
+    let mut bmp = Vec::with_capacity((40 + xor_len + and_len) as usize);
+
+    // BITMAPINFOHEADER, height counts both the XOR and AND bitmaps
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    bmp.extend_from_slice(&(width as i32).to_le_bytes());
+    bmp.extend_from_slice(&((height * 2) as i32).to_le_bytes());
+    bmp.extend_from_slice(&1u16.to_le_bytes());
+    bmp.extend_from_slice(&32u16.to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+    bmp.extend_from_slice(&(xor_len + and_len).to_le_bytes());
+    bmp.extend_from_slice(&[0u8; 16]);
+
+    // Rows are stored bottom-up
+    for y in (0..height).rev() {
+        for x in 0..width {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            bmp.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+
+    for y in (0..height).rev() {
+        let mut row = vec![0u8; mask_stride as usize];
+        for x in 0..width {
+            if rgba.get_pixel(x, y)[3] == 0 {
+                row[(x / 8) as usize] |= 0x80 >> (x % 8);
+            }
+        }
+        bmp.extend_from_slice(&row);
+    }
+
+    bmp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+    use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+    fn frame(size: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(size, size, Rgba([10, 20, 30, 255])))
+    }
+
+    #[test]
+    fn packs_bmp_and_png_entries() {
+        let ico = encode(&[frame(16), frame(32), frame(256)]).unwrap();
+
+        assert_eq!(&ico[0..6], &[0, 0, 1, 0, 3, 0]);
+
+        let entry = |i: usize| &ico[6 + 16 * i..6 + 16 * (i + 1)];
+        let offset = |i: usize| u32::from_le_bytes(entry(i)[12..16].try_into().unwrap()) as usize;
+
+        assert_eq!(entry(0)[0], 16);
+        assert_eq!(entry(2)[0], 0);
+
+        // Small entries are DIBs with a doubled height, 256px is PNG
+        assert_eq!(&ico[offset(0)..offset(0) + 4], &40u32.to_le_bytes());
+        assert_eq!(&ico[offset(0) + 8..offset(0) + 12], &32i32.to_le_bytes());
+        assert_eq!(&ico[offset(2) + 1..offset(2) + 4], b"PNG");
+    }
+
+    #[test]
+    fn decodes_with_the_image_crate() {
+        let ico = encode(&[frame(16), frame(48), frame(256)]).unwrap();
+        let decoded = image::load_from_memory_with_format(&ico, image::ImageFormat::Ico).unwrap();
+        assert_eq!(decoded.dimensions(), (256, 256));
+
+        // Largest BMP entry, top row transparent to check row order
+        let mut bmp_frame = RgbaImage::from_pixel(48, 48, Rgba([10, 20, 30, 255]));
+        for x in 0..48 {
+            bmp_frame.put_pixel(x, 0, Rgba([0, 0, 0, 0]));
+        }
+        let ico = encode(&[frame(16), DynamicImage::ImageRgba8(bmp_frame)]).unwrap();
+        let decoded = image::load_from_memory_with_format(&ico, image::ImageFormat::Ico).unwrap();
+        assert_eq!(decoded.dimensions(), (48, 48));
+        assert_eq!(decoded.get_pixel(5, 0)[3], 0);
+        assert_eq!(decoded.get_pixel(5, 47), Rgba([10, 20, 30, 255]));
+    }
+}
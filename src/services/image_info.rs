@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use gettextrs::gettext;
+use image::{ImageFormat, RgbaImage};
+use std::path::Path;
+
+/// What the image list shows about a source image
+#[derive(Debug, Clone)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Short format name such as `PNG`, if the format was recognised
+    pub format: Option<String>,
+    /// Size of the file in bytes
+    pub file_size: u64,
+}
+
+#[derive(Debug)]
+pub struct Preview {
+    pub info: ImageInfo,
+    /// At most `max_side` pixels on its longest side, keeping the aspect ratio
+    pub thumbnail: RgbaImage,
+}
+
+/// Decode `path` and make a thumbnail of it. This reads the whole image, so
+/// call it off the main thread.
+pub fn load_preview(path: &Path, max_side: u32) -> Result<Preview> {
+    let file_size = std::fs::metadata(path)
+        .with_context(|| format!("{} {}", gettext("Could not read:"), path.display()))?
+        .len();
+
+    let reader = image::io::Reader::open(path)
+        .with_context(|| format!("{} {}", gettext("Could not open:"), path.display()))?
+        .with_guessed_format()?;
+    let format = reader.format().map(format_name);
+    let img = reader.decode()
+        .with_context(|| format!("{} {}", gettext("Could not decode:"), path.display()))?;
+
+    Ok(Preview {
+        info: ImageInfo {
+            width: img.width(),
+            height: img.height(),
+            format,
+            file_size,
+        },
+        thumbnail: img.thumbnail(max_side, max_side).to_rgba8(),
+    })
+}
+
+fn format_name(format: ImageFormat) -> String {
+    match format {
+        ImageFormat::Jpeg => "JPEG".to_string(),
+        ImageFormat::WebP => "WebP".to_string(),
+        other => format!("{:?}", other).to_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load_preview;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn reads_info_and_scales_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("jair-preview-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // The extension is wrong on purpose, the format comes from the contents
+        let path = dir.join("wide.jpg");
+        RgbaImage::from_pixel(200, 50, Rgba([0, 128, 255, 255]))
+            .save_with_format(&path, image::ImageFormat::Png)
+            .unwrap();
+
+        let preview = load_preview(&path, 64).unwrap();
+        assert_eq!((preview.info.width, preview.info.height), (200, 50));
+        assert_eq!(preview.info.format.as_deref(), Some("PNG"));
+        assert_eq!(preview.info.file_size, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(preview.thumbnail.dimensions(), (64, 16));
+
+        assert!(load_preview(&dir.join("missing.png"), 64).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
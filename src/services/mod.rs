@@ -2,3 +2,11 @@
 // By Leandro Santiago
 
 pub mod processor;
+pub mod resampler;
+pub mod appiconset;
+pub mod android_res;
+pub mod ico;
+pub mod icns;
+pub mod user_presets;
+pub mod folder_scan;
+pub mod image_info;
@@ -1,53 +1,809 @@
-use anyhow::{Context, Result};
-use crate::models::Size;
-use image::{imageops::FilterType, GenericImageView};
+use gettextrs::gettext;
+use crate::models::{
+    CropHint, EncoderSettings, OutputFormat, OutputLayout, PngCompression, PngFilter, ResampleStrategy,
+    ResizeBackend, ResizeMode, Size, UpscalePolicy,
+};
+use crate::models::user_preset::is_valid_size_name;
+use crate::services::resampler::{resampler, Resampler};
+use crate::services::{android_res, appiconset, icns, ico};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::png::{self, PngEncoder};
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::{
+    imageops, ColorType, DynamicImage, GenericImageView, ImageEncoder, ImageError, Rgba,
+    RgbaImage,
+};
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Settings shared by every image of a run
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    pub output_format: OutputFormat,
+    pub encoder: EncoderSettings,
+    pub layout: OutputLayout,
+    /// Used for sizes that don't set their own mode
+    pub resize_mode: ResizeMode,
+    /// What to do with sizes larger than the source image
+    pub upscale: UpscalePolicy,
+    /// Whether sizes are resampled from the full image or from reduced copies
+    pub resample: ResampleStrategy,
+    /// Implementation the Lanczos3 resampling runs on
+    pub resize_backend: ResizeBackend,
+    /// What to keep when cropping in cover mode, keyed by input path
+    pub crop_hints: HashMap<PathBuf, CropHint>,
+    /// For inputs found in a recursive folder scan, the subfolder they came
+    /// from, recreated under the output directory. Keyed by input path.
+    pub source_folders: HashMap<PathBuf, PathBuf>,
+    /// Checked between images and between sizes
+    pub cancel: CancelToken,
+    /// Worker threads for a batch, 0 uses one per CPU core
+    pub threads: usize,
+    /// Told about every output as it is written
+    pub progress: ProgressHandler,
+}
+
+/// Shared flag to stop a run from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Sent to the `ProgressHandler` while a batch runs, from its worker threads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// `size` of `input` was written. Sizes packed into one `.ico` or
+    /// `.icns` are reported together once the file is written, the first
+    /// with its bytes and the rest with 0, so bytes add up over a run.
+    /// Outputs are staged until the whole image is done, so these bytes may
+    /// still be thrown away if a later size fails.
+    Output { input: PathBuf, size: String, bytes: u64 },
+    /// `input` is done, whether it succeeded, failed or was cancelled.
+    /// `bytes` is what ended up in the output folder, 0 unless it succeeded.
+    Finished { input: PathBuf, bytes: u64 },
+}
+
+/// Callback for `Progress` updates, does nothing by default
+#[derive(Clone, Default)]
+pub struct ProgressHandler(Option<Arc<dyn Fn(Progress) + Send + Sync>>);
+
+impl ProgressHandler {
+    pub fn new(handler: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        ProgressHandler(Some(Arc::new(handler)))
+    }
+
+    pub fn report(&self, progress: Progress) {
+        if let Some(handler) = &self.0 {
+            handler(progress);
+        }
+    }
+}
+
+impl std::fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProgressHandler").field(&self.0.is_some()).finish()
+    }
+}
+
+/// Why an image, or one of its sizes, could not be written. Messages are
+/// translated; match on the variant to react to a particular failure.
+#[derive(Debug)]
+pub enum ProcessError {
+    /// The input, or an image it needs, could not be read from disk
+    Read { path: PathBuf, source: std::io::Error },
+    /// The input is not in a format that can be decoded
+    UnsupportedFormat { path: PathBuf },
+    /// The input is damaged or not really an image
+    Decode { path: PathBuf, source: ImageError },
+    /// A size without dimensions, or with a name that can't be a file name
+    InvalidSize { size: String },
+    /// An Android resource name with characters resources can't use
+    InvalidResourceName { name: String },
+    /// The upscale policy is `fail` and these sizes are larger than the image
+    TooSmall { width: u32, height: u32, sizes: Vec<String> },
+    /// An output folder could not be created
+    CreateDir { path: PathBuf, source: std::io::Error },
+    /// An output image could not be encoded
+    Encode { path: PathBuf, source: Box<dyn std::error::Error + Send + Sync> },
+    /// An output file could not be written or moved into place
+    Write { path: PathBuf, source: std::io::Error },
+    /// The run was cancelled before the image was finished
+    Cancelled,
+    /// `source` happened while writing `size`
+    InSize { size: String, source: Box<ProcessError> },
+}
+
+pub type Result<T> = std::result::Result<T, ProcessError>;
+
+impl ProcessError {
+    /// The error itself, without the size it happened in
+    pub fn root(&self) -> &ProcessError {
+        match self {
+            ProcessError::InSize { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// The size being written when the error happened, if it was about one
+    pub fn size(&self) -> Option<&str> {
+        match self {
+            ProcessError::InSize { size, .. } => Some(size),
+            _ => None,
+        }
+    }
+
+    /// What the user can do about it, when there is something to say
+    pub fn hint(&self) -> Option<String> {
+        match self.root() {
+            ProcessError::UnsupportedFormat { .. } => {
+                Some(gettext("Convert the image to PNG, JPEG, WebP, GIF, BMP, TIFF or ICO first"))
+            }
+            ProcessError::Decode { .. } => Some(gettext("The file may be damaged or not an image")),
+            ProcessError::TooSmall { .. } => {
+                Some(gettext("Use a larger source image or another upscale policy"))
+            }
+            ProcessError::CreateDir { source, .. } | ProcessError::Write { source, .. } => {
+                Some(match source.kind() {
+                    std::io::ErrorKind::PermissionDenied => {
+                        gettext("You don't have permission to write to the output folder")
+                    }
+                    std::io::ErrorKind::StorageFull => gettext("The disk is full"),
+                    _ => gettext("Check that the output folder is writable and the disk is not full"),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The message followed by the errors that caused it
+    pub fn details(&self) -> String {
+        let mut details = self.root().to_string();
+        let mut source = std::error::Error::source(self.root());
+        while let Some(error) = source {
+            details.push_str(": ");
+            details.push_str(&error.to_string());
+            source = error.source();
+        }
+        details
+    }
+}
+
+impl std::fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessError::Read { path, .. } => write!(f, "{} {}", gettext("Could not read:"), path.display()),
+            ProcessError::UnsupportedFormat { path } => {
+                write!(f, "{} {}", gettext("Unsupported image format:"), path.display())
+            }
+            ProcessError::Decode { path, .. } => write!(f, "{} {}", gettext("Could not decode:"), path.display()),
+            ProcessError::InvalidSize { size } => write!(
+                f,
+                "{} {}",
+                gettext("Invalid size, it needs a width or a height and a name without slashes:"),
+                size
+            ),
+            ProcessError::InvalidResourceName { name } => write!(
+                f,
+                "{} {}",
+                gettext("Invalid Android resource name, use lowercase letters, digits and underscores:"),
+                name
+            ),
+            ProcessError::TooSmall { width, height, sizes } => write!(
+                f,
+                "{} ({} × {}): {}",
+                gettext("The image is smaller than these sizes"),
+                width,
+                height,
+                sizes.join(", ")
+            ),
+            ProcessError::CreateDir { path, .. } => {
+                write!(f, "{} {}", gettext("Could not create folder:"), path.display())
+            }
+            ProcessError::Encode { path, .. } => write!(f, "{} {}", gettext("Could not encode:"), path.display()),
+            ProcessError::Write { path, .. } => write!(f, "{} {}", gettext("Could not save:"), path.display()),
+            ProcessError::Cancelled => write!(f, "{}", gettext("Processing was cancelled")),
+            ProcessError::InSize { size, source } => write!(f, "{} {}: {}", gettext("Size"), size, source),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProcessError::Read { source, .. }
+            | ProcessError::CreateDir { source, .. }
+            | ProcessError::Write { source, .. } => Some(source),
+            ProcessError::Decode { source, .. } => Some(source),
+            ProcessError::Encode { source, .. } => Some(source.as_ref()),
+            ProcessError::InSize { source, .. } => source.source(),
+            _ => None,
+        }
+    }
+}
+
+/// Tag an error with the size being written: `.map_err(in_size(size))?`
+pub(crate) fn in_size(size: &Size) -> impl FnOnce(ProcessError) -> ProcessError + '_ {
+    move |error| ProcessError::InSize { size: size.name.clone(), source: Box::new(error) }
+}
+
+/// An image that could not be processed, as listed in reports
+#[derive(Debug, Clone)]
+pub struct ImageFailure {
+    pub input: PathBuf,
+    /// The size being written when it failed, if the error was about one
+    pub size: Option<String>,
+    pub message: String,
+    /// What the user can do about it, if anything
+    pub hint: Option<String>,
+}
+
+impl ImageFailure {
+    pub fn new(input: &Path, error: &ProcessError) -> Self {
+        ImageFailure {
+            input: input.to_path_buf(),
+            size: error.size().map(str::to_string),
+            message: error.details(),
+            hint: error.hint(),
+        }
+    }
+}
+
+/// Open an image, telling missing files, unknown formats and damaged
+/// images apart
+pub(crate) fn open_image(path: &Path) -> Result<DynamicImage> {
+    image::open(path).map_err(|error| match error {
+        ImageError::IoError(source) => ProcessError::Read { path: path.to_path_buf(), source },
+        ImageError::Unsupported(_) => ProcessError::UnsupportedFormat { path: path.to_path_buf() },
+        source => ProcessError::Decode { path: path.to_path_buf(), source },
+    })
+}
+
+pub(crate) fn create_dir_all(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path).map_err(|source| ProcessError::CreateDir { path: path.to_path_buf(), source })
+}
+
+pub(crate) fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    std::fs::write(path, contents).map_err(|source| ProcessError::Write { path: path.to_path_buf(), source })
+}
+
+impl ProcessOptions {
+    pub fn crop_hint(&self, input: &Path) -> Option<CropHint> {
+        self.crop_hints.get(input).copied()
+    }
+
+    /// `out_dir`, or the folder under it that mirrors where `input` was found
+    pub fn output_root(&self, input: &Path, out_dir: &Path) -> PathBuf {
+        match self.source_folders.get(input) {
+            Some(subfolder) => out_dir.join(subfolder),
+            None => out_dir.to_path_buf(),
+        }
+    }
+
+    /// Report that `size` of `input` was written in `bytes`
+    pub fn report_output(&self, input: &Path, size: &Size, bytes: u64) {
+        self.progress.report(Progress::Output {
+            input: input.to_path_buf(),
+            size: size.name.clone(),
+            bytes,
+        });
+    }
+
+    /// `Err(ProcessError::Cancelled)` once the run has been cancelled
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            Err(ProcessError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// What was written for one input
+#[derive(Debug, Default)]
+pub struct ImageReport {
+    pub saved: Vec<PathBuf>,
+    /// Sizes larger than the source that were enlarged anyway
+    pub upscaled: Vec<String>,
+    /// Sizes larger than the source that were left out
+    pub skipped: Vec<String>,
+}
 
 pub fn resize_and_save(
     input: &Path,
     out_dir: &Path,
     sizes: &[Size],
-    output_format_png: bool,
+    output_format: OutputFormat,
 ) -> Result<Vec<PathBuf>> {
-    let img = image::open(input)
-        .with_context(|| format!("No se pudo abrir la imagen: {}", input.display()))?;
+    let options = ProcessOptions {
+        output_format,
+        ..ProcessOptions::default()
+    };
+    resize_and_save_with_options(input, out_dir, sizes, &options).map(|report| report.saved)
+}
 
-    let mut saved = Vec::new();
+/// Resize one image and arrange the results according to `options.layout`.
+/// Sizes larger than the image are handled as `options.upscale` says.
+/// Files only appear in `out_dir` once every size is written, so an image
+/// that fails or is cancelled leaves nothing behind.
+pub fn resize_and_save_with_options(
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<ImageReport> {
+    options.check_cancelled()?;
 
-    for s in sizes {
-        let (width, height) = resolved_dimensions(img.dimensions(), s);
-        let resized = img.resize(width, height, FilterType::Lanczos3);
+    if let Some(size) = sizes.iter().find(|size| {
+        (size.width == 0 && size.height == 0) || !is_valid_size_name(&size.name)
+    }) {
+        return Err(ProcessError::InvalidSize { size: size.name.clone() });
+    }
 
-        let folder = out_dir.join(&s.name);
-        std::fs::create_dir_all(&folder)
-            .with_context(|| format!("No se pudo crear directorio: {}", folder.display()))?;
+    let img = open_image(input)?;
 
-        let filename = input.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("image");
+    let (width, height) = img.dimensions();
+    let (larger, fitting): (Vec<&Size>, Vec<&Size>) = sizes
+        .iter()
+        .partition(|size| size.upscales(width, height, options.resize_mode, options.crop_hint(input)));
+    let larger_names: Vec<String> = larger.iter().map(|size| size.name.clone()).collect();
 
-        let out_path = if output_format_png {
-            folder.join(format!("{}-{}.png", filename, s.name))
+    let mut report = ImageReport::default();
+    let sizes: Vec<Size> = match options.upscale {
+        UpscalePolicy::Allow => sizes.to_vec(),
+        UpscalePolicy::Warn => {
+            report.upscaled = larger_names;
+            sizes.to_vec()
+        }
+        UpscalePolicy::Skip => {
+            report.skipped = larger_names;
+            fitting.into_iter().cloned().collect()
+        }
+        UpscalePolicy::Fail if !larger.is_empty() => {
+            return Err(ProcessError::TooSmall { width, height, sizes: larger_names });
+        }
+        UpscalePolicy::Fail => sizes.to_vec(),
+    };
+
+    if sizes.is_empty() {
+        return Ok(report);
+    }
+
+    let resampler = resampler(options.resize_backend);
+    let img = match options.resample {
+        ResampleStrategy::Direct => SourceImage::new(img, resampler),
+        ResampleStrategy::Progressive => {
+            SourceImage::progressive(img, &sizes, options.resize_mode, options.crop_hint(input), resampler)
+        }
+    };
+
+    report.saved = write_staged(out_dir, |out_dir| match &options.layout {
+        OutputLayout::SizeFolders => save_in_size_folders(&img, input, out_dir, &sizes, options),
+        OutputLayout::AppIconSet => appiconset::save(&img, input, out_dir, &sizes, options),
+        OutputLayout::AndroidRes { resource_name, adaptive } => {
+            android_res::save(&img, input, out_dir, &sizes, options, resource_name, adaptive)
+        }
+        OutputLayout::Ico => ico::save(&img, input, out_dir, &sizes, options),
+        OutputLayout::MacIconSet => icns::save_iconset(&img, input, out_dir, &sizes, options),
+        OutputLayout::Icns => icns::save_icns(&img, input, out_dir, &sizes, options),
+    })?;
+    Ok(report)
+}
+
+/// Run `write` against a hidden folder inside `out_dir`, then move what it
+/// wrote into `out_dir`. The folder is removed whether `write` succeeds or not.
+fn write_staged(
+    out_dir: &Path,
+    write: impl FnOnce(&Path) -> Result<Vec<PathBuf>>,
+) -> Result<Vec<PathBuf>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let staging = out_dir.join(format!(
+        ".jair-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = write(&staging).and_then(|saved| {
+        if staging.is_dir() {
+            move_tree(&staging, out_dir)?;
+        }
+        Ok(saved
+            .into_iter()
+            .map(|path| match path.strip_prefix(&staging) {
+                Ok(relative) => out_dir.join(relative),
+                Err(_) => path,
+            })
+            .collect())
+    });
+
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// Move every file under `from` to the same place under `to`, replacing
+/// files that already exist
+fn move_tree(from: &Path, to: &Path) -> Result<()> {
+    create_dir_all(to)?;
+
+    let read_error = |source| ProcessError::Read { path: from.to_path_buf(), source };
+    for entry in std::fs::read_dir(from).map_err(read_error)? {
+        let entry = entry.map_err(read_error)?;
+        let target = to.join(entry.file_name());
+        if entry.file_type().map_err(read_error)?.is_dir() {
+            move_tree(&entry.path(), &target)?;
         } else {
-            folder.join(format!("{}-{}.jpg", filename, s.name))
-        };
+            std::fs::rename(entry.path(), &target)
+                .map_err(|source| ProcessError::Write { path: target.clone(), source })?;
+        }
+    }
+    Ok(())
+}
+
+/// Write each size into its own folder named after `Size.name`
+pub(crate) fn save_in_size_folders(
+    img: &SourceImage,
+    input: &Path,
+    out_dir: &Path,
+    sizes: &[Size],
+    options: &ProcessOptions,
+) -> Result<Vec<PathBuf>> {
+    sizes
+        .par_iter()
+        .map(|s| {
+            options.check_cancelled()?;
+            let resized = img.resize_to(s, options.resize_mode, options.crop_hint(input));
+
+            let folder = out_dir.join(&s.name);
+            create_dir_all(&folder).map_err(in_size(s))?;
+
+            let filename = input.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("image");
+
+            let out_path = folder.join(format!("{}-{}.{}", filename, s.name, options.output_format.extension()));
+
+            let bytes = save_image(&resized, &out_path, options.output_format, &options.encoder)
+                .map_err(in_size(s))?;
+            options.report_output(input, s, bytes);
+            Ok(out_path)
+        })
+        .collect()
+}
+
+/// Resize to `size`, using its own mode if it has one and `default_mode` otherwise.
+/// `crop` only matters in cover mode.
+pub(crate) fn resize_to(
+    img: &DynamicImage,
+    size: &Size,
+    default_mode: ResizeMode,
+    crop: Option<CropHint>,
+    resampler: &dyn Resampler,
+) -> DynamicImage {
+    let (width, height) = resolved_dimensions(img.dimensions(), size);
+    let fit = |img: &DynamicImage| {
+        let (fit_width, fit_height) = fit_dimensions(img.dimensions(), (width, height));
+        resampler.resize_exact(img, fit_width, fit_height)
+    };
+
+    match size.resize_mode(default_mode, crop) {
+        ResizeMode::Fit => fit(img),
+        ResizeMode::Contain(padding) => pad_to(&fit(img), width, height, padding),
+        ResizeMode::Cover => {
+            let (x, y, crop_width, crop_height) = cover_crop(img.dimensions(), (width, height), crop);
+            resampler.resize_exact(&img.crop_imm(x, y, crop_width, crop_height), width, height)
+        }
+        ResizeMode::Stretch => resampler.resize_exact(img, width, height),
+    }
+}
+
+/// The decoded input. Progressive sources also keep copies of it halved
+/// again and again, each made from the one before, so small sizes are
+/// resampled from a nearby copy instead of the full image.
+pub struct SourceImage<'a> {
+    image: DynamicImage,
+    /// Largest first, each half the size of the one before
+    levels: Vec<DynamicImage>,
+    resampler: &'a dyn Resampler,
+}
 
-        if output_format_png {
-            resized
-                .save_with_format(&out_path, image::ImageFormat::Png)
-                .with_context(|| format!("Error guardando {}", out_path.display()))?;
+impl<'a> SourceImage<'a> {
+    /// Resample every size from `image` itself
+    pub fn new(image: DynamicImage, resampler: &'a dyn Resampler) -> Self {
+        SourceImage { image, levels: Vec::new(), resampler }
+    }
+
+    /// Halve `image` for as long as the copy stays at least twice the
+    /// resolution the smallest of `sizes` needs
+    pub fn progressive(
+        image: DynamicImage,
+        sizes: &[Size],
+        default_mode: ResizeMode,
+        crop: Option<CropHint>,
+        resampler: &'a dyn Resampler,
+    ) -> Self {
+        let dimensions = image.dimensions();
+        let mut scales: Vec<f64> = sizes
+            .iter()
+            .map(|size| resize_plan(dimensions, size, default_mode, crop).scale())
+            .collect();
+        scales.sort_by(f64::total_cmp);
+
+        let mut levels: Vec<DynamicImage> = Vec::new();
+        if let Some(&smallest) = scales.first() {
+            loop {
+                let previous = levels.last().unwrap_or(&image);
+                let (width, height) = (previous.width() / 2, previous.height() / 2);
+                let scale = (f64::from(width) / f64::from(dimensions.0))
+                    .min(f64::from(height) / f64::from(dimensions.1));
+                if width == 0 || height == 0 || scale < MIN_LEVEL_RATIO * smallest {
+                    break;
+                }
+                levels.push(resampler.resize_exact(previous, width, height));
+            }
+        }
+
+        SourceImage { image, levels, resampler }
+    }
+
+    /// The full-resolution image
+    pub fn image(&self) -> &DynamicImage {
+        &self.image
+    }
+
+    /// Resize to `size` like `resize_to`, from the smallest level that still
+    /// has `MIN_LEVEL_RATIO` times the pixels the size needs
+    pub fn resize_to(&self, size: &Size, default_mode: ResizeMode, crop: Option<CropHint>) -> DynamicImage {
+        if self.levels.is_empty() {
+            return resize_to(&self.image, size, default_mode, crop, self.resampler);
+        }
+
+        let plan = resize_plan(self.image.dimensions(), size, default_mode, crop);
+        let level = self
+            .levels
+            .iter()
+            .rev()
+            .find(|level| plan.fits_in(self.image.dimensions(), level.dimensions()))
+            .unwrap_or(&self.image);
+
+        let (x, y, width, height) = plan.region_in(self.image.dimensions(), level.dimensions());
+        let resized = if (x, y, width, height) == (0, 0, level.width(), level.height()) {
+            self.resampler.resize_exact(level, plan.scaled.0, plan.scaled.1)
         } else {
-            let mut fout = std::fs::File::create(&out_path)?;
-            let quality = 90u8;
-            resized.write_to(&mut std::io::BufWriter::new(&mut fout), image::ImageOutputFormat::Jpeg(quality))
-                .with_context(|| format!("Error guardando {}", out_path.display()))?;
+            self.resampler.resize_exact(&level.crop_imm(x, y, width, height), plan.scaled.0, plan.scaled.1)
+        };
+
+        match plan.padding {
+            Some((width, height, padding)) => pad_to(&resized, width, height, padding),
+            None => resized,
         }
+    }
+}
+
+/// A level is only used for sizes it has at least this many times the
+/// resolution of, so the final Lanczos3 pass still has detail to work with
+const MIN_LEVEL_RATIO: f64 = 2.0;
 
-        saved.push(out_path);
+/// What `resize_to` does for a size, worked out on the source dimensions
+/// so that any level gives the same output dimensions
+struct ResizePlan {
+    /// Region of the source that is resampled: x, y, width and height
+    region: (u32, u32, u32, u32),
+    /// Dimensions the region is resampled to
+    scaled: (u32, u32),
+    /// Canvas dimensions and color for contain
+    padding: Option<(u32, u32, [u8; 4])>,
+}
+
+impl ResizePlan {
+    /// Largest fraction of its source dimensions the region shrinks to
+    fn scale(&self) -> f64 {
+        let (_, _, width, height) = self.region;
+        (f64::from(self.scaled.0) / f64::from(width)).max(f64::from(self.scaled.1) / f64::from(height))
     }
 
-    Ok(saved)
+    fn fits_in(&self, source: (u32, u32), level: (u32, u32)) -> bool {
+        let (_, _, width, height) = self.region_in(source, level);
+        f64::from(width) >= MIN_LEVEL_RATIO * f64::from(self.scaled.0)
+            && f64::from(height) >= MIN_LEVEL_RATIO * f64::from(self.scaled.1)
+    }
+
+    /// The region scaled to a level of `level` dimensions
+    fn region_in(&self, source: (u32, u32), level: (u32, u32)) -> (u32, u32, u32, u32) {
+        let scale_x = f64::from(level.0) / f64::from(source.0);
+        let scale_y = f64::from(level.1) / f64::from(source.1);
+        let (x, y, width, height) = self.region;
+
+        let x = ((f64::from(x) * scale_x).round() as u32).min(level.0 - 1);
+        let y = ((f64::from(y) * scale_y).round() as u32).min(level.1 - 1);
+        let width = ((f64::from(width) * scale_x).round() as u32).clamp(1, level.0 - x);
+        let height = ((f64::from(height) * scale_y).round() as u32).clamp(1, level.1 - y);
+        (x, y, width, height)
+    }
+}
+
+fn resize_plan(source: (u32, u32), size: &Size, default_mode: ResizeMode, crop: Option<CropHint>) -> ResizePlan {
+    let (width, height) = resolved_dimensions(source, size);
+    let whole = (0, 0, source.0, source.1);
+
+    match size.resize_mode(default_mode, crop) {
+        ResizeMode::Fit => ResizePlan { region: whole, scaled: fit_dimensions(source, (width, height)), padding: None },
+        ResizeMode::Contain(padding) => ResizePlan {
+            region: whole,
+            scaled: fit_dimensions(source, (width, height)),
+            padding: Some((width, height, padding)),
+        },
+        ResizeMode::Cover => ResizePlan {
+            region: cover_crop(source, (width, height), crop),
+            scaled: (width, height),
+            padding: None,
+        },
+        ResizeMode::Stretch => ResizePlan { region: whole, scaled: (width, height), padding: None },
+    }
+}
+
+/// The dimensions `DynamicImage::resize` picks to fit `source` in `bounds`
+fn fit_dimensions(source: (u32, u32), bounds: (u32, u32)) -> (u32, u32) {
+    let ratio = (f64::from(bounds.0) / f64::from(source.0)).min(f64::from(bounds.1) / f64::from(source.1));
+    let width = (f64::from(source.0) * ratio).round().clamp(1.0, f64::from(u32::MAX)) as u32;
+    let height = (f64::from(source.1) * ratio).round().clamp(1.0, f64::from(u32::MAX)) as u32;
+    (width, height)
+}
+
+/// Region of the source with the target's aspect ratio to cover it with.
+/// Without a hint this is the largest centred region.
+fn cover_crop(
+    (src_width, src_height): (u32, u32),
+    (width, height): (u32, u32),
+    hint: Option<CropHint>,
+) -> (u32, u32, u32, u32) {
+    let (src_w, src_h) = (f64::from(src_width), f64::from(src_height));
+    let aspect = f64::from(width) / f64::from(height);
+
+    let (full_w, full_h) = if src_w / src_h > aspect {
+        (src_h * aspect, src_h)
+    } else {
+        (src_w, src_w / aspect)
+    };
+
+    let (crop_w, crop_h, centre_x, centre_y) = match hint {
+        None => (full_w, full_h, src_w / 2.0, src_h / 2.0),
+        Some(CropHint::FocalPoint { x, y }) => (full_w, full_h, x * src_w, y * src_h),
+        Some(CropHint::Region { x, y, width, height }) => {
+            let region_w = (width * src_w).max(1.0);
+            let region_h = (height * src_h).max(1.0);
+            // Smallest window that still contains the whole region
+            let (w, h) = if region_w / region_h > aspect {
+                (region_w, region_w / aspect)
+            } else {
+                (region_h * aspect, region_h)
+            };
+            (w.min(full_w), h.min(full_h), x * src_w + region_w / 2.0, y * src_h + region_h / 2.0)
+        }
+    };
+
+    let crop_w = crop_w.floor().max(1.0);
+    let crop_h = crop_h.floor().max(1.0);
+    let x = (centre_x - crop_w / 2.0).clamp(0.0, src_w - crop_w).round();
+    let y = (centre_y - crop_h / 2.0).clamp(0.0, src_h - crop_h).round();
+    (x as u32, y as u32, crop_w as u32, crop_h as u32)
+}
+
+/// Centre `img` on a `width`x`height` canvas filled with `padding`
+fn pad_to(img: &DynamicImage, width: u32, height: u32, padding: [u8; 4]) -> DynamicImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba(padding));
+    let x = (width - img.width().min(width)) / 2;
+    let y = (height - img.height().min(height)) / 2;
+    imageops::overlay(&mut canvas, &img.to_rgba8(), i64::from(x), i64::from(y));
+
+    let canvas = DynamicImage::ImageRgba8(canvas);
+    if padding[3] == 0xFF && !img.color().has_alpha() {
+        DynamicImage::ImageRgb8(canvas.to_rgb8())
+    } else {
+        canvas
+    }
+}
+
+/// Encode `img` to `out_path`, returning the size of the file written
+pub(crate) fn save_image(
+    img: &DynamicImage,
+    out_path: &Path,
+    output_format: OutputFormat,
+    encoder: &EncoderSettings,
+) -> Result<u64> {
+    let write_error = |source| ProcessError::Write { path: out_path.to_path_buf(), source };
+    let fout = std::fs::File::create(out_path).map_err(write_error)?;
+    let mut writer = std::io::BufWriter::new(fout);
+
+    let result: EncodeResult = match output_format {
+        OutputFormat::Png => {
+            let png_encoder = PngEncoder::new_with_quality(
+                &mut writer,
+                png_compression(encoder.png_compression),
+                png_filter(encoder.png_filter),
+            );
+            img.write_with_encoder(png_encoder).map_err(Into::into)
+        }
+        OutputFormat::Jpeg => write_jpeg(&mut writer, img, encoder),
+        OutputFormat::WebpLossy => {
+            #[allow(deprecated)]
+            let webp_encoder = WebPEncoder::new_with_quality(
+                &mut writer,
+                WebPQuality::lossy(encoder.webp_quality),
+            );
+            write_rgb_or_rgba(webp_encoder, img).map_err(Into::into)
+        }
+        OutputFormat::WebpLossless => {
+            write_rgb_or_rgba(WebPEncoder::new_lossless(&mut writer), img).map_err(Into::into)
+        }
+        OutputFormat::Avif => {
+            write_rgb_or_rgba(AvifEncoder::new(&mut writer), img).map_err(Into::into)
+        }
+    };
+
+    result.map_err(|source| ProcessError::Encode { path: out_path.to_path_buf(), source })?;
+    writer.flush().map_err(write_error)?;
+    Ok(writer.get_ref().metadata().map_err(write_error)?.len())
+}
+
+type EncodeResult = std::result::Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// The built-in JPEG encoder only writes baseline files, so JPEG goes
+/// through `jpeg-encoder`, which also supports progressive output.
+/// JPEG has no alpha channel; transparent pixels keep their color.
+fn write_jpeg<W: Write>(writer: W, img: &DynamicImage, settings: &EncoderSettings) -> EncodeResult {
+    let width = u16::try_from(img.width())?;
+    let height = u16::try_from(img.height())?;
+
+    let mut encoder = jpeg_encoder::Encoder::new(writer, settings.jpeg_quality);
+    encoder.set_progressive(settings.jpeg_progressive);
+
+    if img.color().has_color() {
+        encoder.encode(&img.to_rgb8(), width, height, jpeg_encoder::ColorType::Rgb)?;
+    } else {
+        encoder.encode(&img.to_luma8(), width, height, jpeg_encoder::ColorType::Luma)?;
+    }
+    Ok(())
+}
+
+fn png_compression(compression: PngCompression) -> png::CompressionType {
+    match compression {
+        PngCompression::Fast => png::CompressionType::Fast,
+        PngCompression::Default => png::CompressionType::Default,
+        PngCompression::Best => png::CompressionType::Best,
+    }
+}
+
+fn png_filter(filter: PngFilter) -> png::FilterType {
+    match filter {
+        PngFilter::NoFilter => png::FilterType::NoFilter,
+        PngFilter::Sub => png::FilterType::Sub,
+        PngFilter::Up => png::FilterType::Up,
+        PngFilter::Avg => png::FilterType::Avg,
+        PngFilter::Paeth => png::FilterType::Paeth,
+        PngFilter::Adaptive => png::FilterType::Adaptive,
+    }
+}
+
+/// WebP and AVIF encoders only accept 8-bit RGB or RGBA buffers
+fn write_rgb_or_rgba<E: ImageEncoder>(encoder: E, img: &DynamicImage) -> image::ImageResult<()> {
+    if img.color().has_alpha() {
+        let rgba = img.to_rgba8();
+        encoder.write_image(&rgba, rgba.width(), rgba.height(), ColorType::Rgba8)
+    } else {
+        let rgb = img.to_rgb8();
+        encoder.write_image(&rgb, rgb.width(), rgb.height(), ColorType::Rgb8)
+    }
 }
 
 fn resolved_dimensions((original_width, original_height): (u32, u32), size: &Size) -> (u32, u32) {
@@ -68,33 +824,19 @@ fn resolved_dimensions((original_width, original_height): (u32, u32), size: &Siz
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::resolved_dimensions;
-    use crate::models::Size;
-
-    #[test]
-    fn calculates_missing_dimension_from_aspect_ratio() {
-        let width_only = Size {
-            width: 1000,
-            height: 0,
-            name: String::new(),
-        };
-        assert_eq!(
-            resolved_dimensions((4000, 3000), &width_only),
-            (1000, 750)
-        );
-
-        let height_only = Size {
-            width: 0,
-            height: 600,
-            name: String::new(),
-        };
-        assert_eq!(
-            resolved_dimensions((4000, 3000), &height_only),
-            (800, 600)
-        );
+/// Output directory for one input of a batch.
+/// Project layouts such as `AppIcon.appiconset` or `res/` have fixed file
+/// names, so when several images are processed together each one gets its
+/// own subfolder.
+pub fn output_dir_for(input: &Path, out_dir: &Path, layout: &OutputLayout, input_count: usize) -> PathBuf {
+    if !layout.has_fixed_file_names() || input_count <= 1 {
+        return out_dir.to_path_buf();
     }
+
+    let stem = input.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("image");
+    out_dir.join(stem)
 }
 
 /// Process multiple images in batch
@@ -102,37 +844,49 @@ pub fn batch_resize_and_save(
     inputs: &[PathBuf],
     out_dir: &Path,
     sizes: &[Size],
-    output_format_png: bool,
-) -> Result<Vec<(PathBuf, Result<Vec<PathBuf>>)>> {
-
-    let results: Vec<(PathBuf, Result<Vec<PathBuf>>)> = inputs
+    options: &ProcessOptions,
+) -> Vec<(PathBuf, Result<ImageReport>)> {
+    inputs
         .iter()
         .map(|input| {
-            let result = resize_and_save(input, out_dir, sizes, output_format_png);
+            let root = options.output_root(input, out_dir);
+            let dir = output_dir_for(input, &root, &options.layout, inputs.len());
+            let result = resize_and_save_with_options(input, &dir, sizes, options);
             (input.clone(), result)
         })
-        .collect();
-
-    Ok(results)
+        .collect()
 }
 
-/// Process multiple images in parallel for better performance
+/// Process multiple images in parallel for better performance, on
+/// `options.threads` threads. Sizes of an image are written in parallel too.
 pub fn batch_resize_and_save_parallel(
     inputs: &[PathBuf],
     out_dir: &Path,
     sizes: &[Size],
-    output_format_png: bool,
-) -> Result<Vec<(PathBuf, Result<Vec<PathBuf>>)>> {
-
-    let results: Vec<(PathBuf, Result<Vec<PathBuf>>)> = inputs
-        .par_iter()
-        .map(|input| {
-            let result = resize_and_save(input, out_dir, sizes, output_format_png);
-            (input.clone(), result)
-        })
-        .collect();
+    options: &ProcessOptions,
+) -> Vec<(PathBuf, Result<ImageReport>)> {
+    let run = || {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let root = options.output_root(input, out_dir);
+                let dir = output_dir_for(input, &root, &options.layout, inputs.len());
+                let result = resize_and_save_with_options(input, &dir, sizes, options);
+                let bytes = match &result {
+                    Ok(report) => report.saved.iter().filter_map(|path| path.metadata().ok()).map(|meta| meta.len()).sum(),
+                    Err(_) => 0,
+                };
+                options.progress.report(Progress::Finished { input: input.clone(), bytes });
+                (input.clone(), result)
+            })
+            .collect()
+    };
 
-    Ok(results)
+    // Fall back to the global pool if a dedicated one can't be started
+    match rayon::ThreadPoolBuilder::new().num_threads(options.threads).build() {
+        Ok(pool) => pool.install(run),
+        Err(_) => run(),
+    }
 }
 
 /// Validate if a file is a supported image format
@@ -152,12 +906,14 @@ pub fn is_supported_image(path: &Path) -> bool {
 pub fn get_images_from_directory(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut images = Vec::new();
 
+    let read_error = |source| ProcessError::Read { path: dir.to_path_buf(), source };
     if !dir.is_dir() {
-        return Err(anyhow::anyhow!("Path is not a directory: {}", dir.display()));
+        let source = std::io::Error::new(std::io::ErrorKind::InvalidInput, gettext("Not a folder"));
+        return Err(read_error(source));
     }
 
-    for entry in std::fs::read_dir(dir)? {
-        let entry = entry?;
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let entry = entry.map_err(read_error)?;
         let path = entry.path();
 
         if path.is_file() && is_supported_image(&path) {
@@ -167,3 +923,369 @@ pub fn get_images_from_directory(dir: &Path) -> Result<Vec<PathBuf>> {
 
     Ok(images)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cover_crop, output_dir_for, resize_and_save_with_options, resize_to, resolved_dimensions, save_image, SourceImage,
+        write_staged, batch_resize_and_save_parallel, ImageFailure, ProcessError, ProcessOptions, Progress,
+        ProgressHandler,
+    };
+    use crate::models::{
+        parse_size_list, CropHint, EncoderSettings, OutputFormat, OutputLayout, ResizeBackend, ResizeMode, Size,
+        UpscalePolicy,
+    };
+    use crate::services::resampler::{resampler, ReferenceResampler};
+    use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn calculates_missing_dimension_from_aspect_ratio() {
+        let width_only = Size::new("", 1000, 0);
+        assert_eq!(
+            resolved_dimensions((4000, 3000), &width_only),
+            (1000, 750)
+        );
+
+        let height_only = Size::new("", 0, 600);
+        assert_eq!(
+            resolved_dimensions((4000, 3000), &height_only),
+            (800, 600)
+        );
+    }
+
+    #[test]
+    fn exact_modes_match_the_requested_size() {
+        let square = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(600, 600, image::Rgb([255, 0, 0])));
+        let feature_graphic = Size::new("feature-graphic", 1024, 500);
+
+        for backend in ResizeBackend::all() {
+            let resampler = resampler(backend);
+            // Presets come out at their exact dimensions unless fit is asked for
+            assert_eq!(
+                resize_to(&square, &feature_graphic, ResizeMode::default(), None, resampler).dimensions(),
+                (1024, 500)
+            );
+            assert_eq!(resize_to(&square, &feature_graphic, ResizeMode::Fit, None, resampler).dimensions(), (500, 500));
+            // A crop hint asks for a crop even when fitting
+            let top = Some(CropHint::FocalPoint { x: 0.5, y: 0.1 });
+            assert_eq!(resize_to(&square, &feature_graphic, ResizeMode::Fit, top, resampler).dimensions(), (1024, 500));
+            for mode in [ResizeMode::Contain([0, 0, 0, 0]), ResizeMode::Cover, ResizeMode::Stretch] {
+                assert_eq!(resize_to(&square, &feature_graphic, mode, None, resampler).dimensions(), (1024, 500));
+            }
+
+            let padded = resize_to(&square, &feature_graphic, ResizeMode::Contain([0, 0, 255, 255]), None, resampler);
+            assert_eq!(padded.to_rgba8().get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+            assert_eq!(padded.to_rgba8().get_pixel(512, 250), &Rgba([255, 0, 0, 255]));
+
+            // The size's own mode wins over the run's mode
+            let cover = Size { mode: Some(ResizeMode::Cover), ..feature_graphic.clone() };
+            assert_eq!(resize_to(&square, &cover, ResizeMode::Fit, None, resampler).dimensions(), (1024, 500));
+        }
+    }
+
+    #[test]
+    fn cover_crops_the_centre() {
+        assert_eq!(cover_crop((600, 600), (1024, 500), None), (0, 154, 600, 292));
+        assert_eq!(cover_crop((4000, 3000), (1000, 1000), None), (500, 0, 3000, 3000));
+        assert_eq!(cover_crop((800, 400), (400, 200), None), (0, 0, 800, 400));
+    }
+
+    #[test]
+    fn cover_honours_focal_points_and_regions() {
+        // The window follows the focal point but stops at the image edges
+        let top = CropHint::FocalPoint { x: 0.5, y: 0.1 };
+        assert_eq!(cover_crop((600, 600), (1024, 500), Some(top)), (0, 0, 600, 292));
+        let right = CropHint::FocalPoint { x: 0.8, y: 0.5 };
+        assert_eq!(cover_crop((4000, 3000), (1000, 1000), Some(right)), (1000, 0, 3000, 3000));
+
+        // A region is kept whole and the crop grows around it to the target ratio
+        let face = CropHint::Region { x: 0.25, y: 0.25, width: 0.25, height: 0.25 };
+        assert_eq!(cover_crop((800, 800), (400, 200), Some(face)), (100, 200, 400, 200));
+
+        // Regions larger than the target ratio allows fall back to the largest window
+        let wide = CropHint::Region { x: 0.0, y: 0.0, width: 1.0, height: 0.5 };
+        assert_eq!(cover_crop((800, 800), (200, 400), Some(wide)), (200, 0, 400, 800));
+    }
+
+    #[test]
+    fn progressive_resampling_matches_direct_output() {
+        let source = DynamicImage::ImageRgba8(RgbaImage::from_fn(1000, 600, |x, y| {
+            Rgba([(x % 256) as u8, (y % 256) as u8, ((x + y) / 8 % 256) as u8, 255])
+        }));
+        let mut sizes = parse_size_list("64x64, 300x, x40, 500x500").unwrap();
+        sizes.push(Size { mode: Some(ResizeMode::Cover), ..parse_size_list("120x90").unwrap().remove(0) });
+        sizes.push(Size { mode: Some(ResizeMode::Contain([0, 0, 0, 0])), ..parse_size_list("48x48").unwrap().remove(0) });
+        sizes.push(Size { mode: Some(ResizeMode::Stretch), ..parse_size_list("32x32").unwrap().remove(0) });
+        let crop = Some(CropHint::focal_point(0.25, 0.5));
+
+        let direct = SourceImage::new(source.clone(), &ReferenceResampler);
+        let progressive = SourceImage::progressive(source, &sizes, ResizeMode::Fit, crop, &ReferenceResampler);
+        assert_eq!(progressive.levels.len(), 3);
+
+        for size in &sizes {
+            let expected = direct.resize_to(size, ResizeMode::Fit, crop).to_rgba8();
+            let actual = progressive.resize_to(size, ResizeMode::Fit, crop).to_rgba8();
+            assert_eq!(actual.dimensions(), expected.dimensions(), "{}", size.name);
+
+            // The pattern repeats every 256px, so shifts would show up here.
+            // Pixels along its sharp edges can differ more, so only the mean
+            // is bounded, as ResampleStrategy documents.
+            let difference: u64 = expected
+                .pixels()
+                .zip(actual.pixels())
+                .flat_map(|(a, b)| a.0.iter().zip(b.0).map(|(a, b)| u64::from(a.abs_diff(b))))
+                .sum();
+            let mean = difference as f64 / (expected.len() as f64);
+            assert!(mean < 1.0, "{}: mean difference {}", size.name, mean);
+        }
+    }
+
+    #[test]
+    fn saves_lossless_webp_with_and_without_alpha() {
+        let dir = std::env::temp_dir().join(format!("jair-webp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let rgba = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 4, Rgba([10, 20, 30, 40])));
+        let rgb = DynamicImage::ImageRgb8(rgba.to_rgb8());
+
+        for (name, img) in [("rgba.webp", &rgba), ("rgb.webp", &rgb)] {
+            let path = dir.join(name);
+            save_image(img, &path, OutputFormat::WebpLossless, &EncoderSettings::default()).unwrap();
+
+            let decoded = image::open(&path).unwrap();
+            assert_eq!(decoded.dimensions(), (8, 4));
+            assert_eq!(decoded.to_rgba8().get_pixel(0, 0), img.to_rgba8().get_pixel(0, 0));
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn writes_progressive_jpeg_when_requested() {
+        let dir = std::env::temp_dir().join(format!("jair-jpeg-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 8, Rgba([200, 100, 50, 255])));
+        // SOF0 marks a baseline frame, SOF2 a progressive one
+        for (progressive, marker) in [(false, [0xFF, 0xC0]), (true, [0xFF, 0xC2])] {
+            let settings = EncoderSettings {
+                jpeg_progressive: progressive,
+                ..EncoderSettings::default()
+            };
+            let path = dir.join(format!("progressive-{}.jpg", progressive));
+            save_image(&img, &path, OutputFormat::Jpeg, &settings).unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(bytes.windows(2).any(|window| window == marker));
+            assert_eq!(image::open(&path).unwrap().dimensions(), (16, 8));
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn project_layouts_get_a_folder_per_input_in_batches() {
+        let input = Path::new("/src/logo.png");
+        let out_dir = Path::new("/out");
+
+        assert_eq!(output_dir_for(input, out_dir, &OutputLayout::SizeFolders, 3), out_dir);
+        assert_eq!(output_dir_for(input, out_dir, &OutputLayout::Ico, 3), out_dir);
+        assert_eq!(output_dir_for(input, out_dir, &OutputLayout::AppIconSet, 1), out_dir);
+        assert_eq!(
+            output_dir_for(input, out_dir, &OutputLayout::android_res(), 3),
+            Path::new("/out/logo")
+        );
+    }
+
+    #[test]
+    fn scanned_inputs_mirror_their_subfolder() {
+        let input = Path::new("/src/icons/app/logo.png");
+        let out_dir = Path::new("/out");
+        let mut options = ProcessOptions::default();
+        options.source_folders.insert(input.to_path_buf(), "icons/app".into());
+
+        assert_eq!(options.output_root(input, out_dir), Path::new("/out/icons/app"));
+        assert_eq!(options.output_root(Path::new("/src/logo.png"), out_dir), out_dir);
+    }
+
+    #[test]
+    fn applies_the_upscale_policy() {
+        let dir = std::env::temp_dir().join(format!("jair-upscale-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("small.png");
+        RgbaImage::from_pixel(32, 32, Rgba([1, 2, 3, 255])).save(&input).unwrap();
+
+        let sizes = parse_size_list("small=16x16, large=64x64").unwrap();
+        let run = |upscale| {
+            let options = ProcessOptions { upscale, ..ProcessOptions::default() };
+            resize_and_save_with_options(&input, &dir.join(upscale.id()), &sizes, &options)
+        };
+
+        let allowed = run(UpscalePolicy::Allow).unwrap();
+        assert_eq!(allowed.saved.len(), 2);
+        assert!(allowed.upscaled.is_empty() && allowed.skipped.is_empty());
+
+        let warned = run(UpscalePolicy::Warn).unwrap();
+        assert_eq!(warned.saved.len(), 2);
+        assert_eq!(warned.upscaled, ["large"]);
+
+        let skipped = run(UpscalePolicy::Skip).unwrap();
+        assert_eq!(skipped.saved, [dir.join("skip/small/small-small.png")]);
+        assert_eq!(skipped.skipped, ["large"]);
+
+        // Nothing is written for an image that fails
+        let error = run(UpscalePolicy::Fail).unwrap_err();
+        assert!(matches!(error, ProcessError::TooSmall { ref sizes, .. } if sizes == &["large"]));
+        assert!(!dir.join("fail").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cancelled_and_failed_images_leave_no_files() {
+        let dir = std::env::temp_dir().join(format!("jair-cancel-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("logo.png");
+        RgbaImage::from_pixel(32, 32, Rgba([1, 2, 3, 255])).save(&input).unwrap();
+
+        let out_dir = dir.join("out");
+        let sizes = parse_size_list("16x16, 8x8").unwrap();
+        let options = ProcessOptions::default();
+        options.cancel.cancel();
+        let error = resize_and_save_with_options(&input, &out_dir, &sizes, &options).unwrap_err();
+        assert!(matches!(error, ProcessError::Cancelled));
+        assert!(!out_dir.exists());
+
+        // A failure after some files were written removes them
+        let result = write_staged(&out_dir, |staging| {
+            std::fs::create_dir_all(staging.join("16")).unwrap();
+            std::fs::write(staging.join("16/logo.png"), b"partial").unwrap();
+            Err(ProcessError::Cancelled)
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 0);
+
+        let saved = write_staged(&out_dir, |staging| {
+            std::fs::create_dir_all(staging.join("16")).unwrap();
+            std::fs::write(staging.join("16/logo.png"), b"done").unwrap();
+            Ok(vec![staging.join("16/logo.png")])
+        })
+        .unwrap();
+        assert_eq!(saved, [out_dir.join("16/logo.png")]);
+        assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failures_name_the_size_being_written() {
+        let dir = std::env::temp_dir().join(format!("jair-failure-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("logo.png");
+        RgbaImage::from_pixel(32, 32, Rgba([1, 2, 3, 255])).save(&input).unwrap();
+
+        // A file where the output folder should be
+        let out_dir = dir.join("out");
+        std::fs::write(&out_dir, b"").unwrap();
+
+        let sizes = parse_size_list("hero=16x16").unwrap();
+        let error = resize_and_save_with_options(&input, &out_dir, &sizes, &ProcessOptions::default()).unwrap_err();
+        assert!(matches!(error.root(), ProcessError::CreateDir { .. }), "{}", error);
+        let failure = ImageFailure::new(&input, &error);
+        assert_eq!(failure.size.as_deref(), Some("hero"));
+        assert!(failure.message.contains("Could not create folder:"), "{}", failure.message);
+        assert!(failure.hint.is_some());
+
+        let error = resize_and_save_with_options(&dir.join("missing.png"), &dir, &sizes, &ProcessOptions::default())
+            .unwrap_err();
+        assert!(matches!(error, ProcessError::Read { .. }), "{}", error);
+        let failure = ImageFailure::new(&dir.join("missing.png"), &error);
+        assert_eq!(failure.size, None);
+        assert!(failure.message.contains("Could not read:"), "{}", failure.message);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn batches_report_every_output_as_it_is_written() {
+        let dir = std::env::temp_dir().join(format!("jair-progress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inputs = vec![dir.join("a.png"), dir.join("b.png")];
+        for input in &inputs {
+            RgbaImage::from_pixel(32, 32, Rgba([1, 2, 3, 255])).save(input).unwrap();
+        }
+
+        let sizes = parse_size_list("16x16, 8x8").unwrap();
+        for layout in [OutputLayout::SizeFolders, OutputLayout::Ico] {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let options = ProcessOptions {
+                layout: layout.clone(),
+                threads: 2,
+                progress: ProgressHandler::new({
+                    let events = events.clone();
+                    move |progress| events.lock().unwrap().push(progress)
+                }),
+                ..ProcessOptions::default()
+            };
+
+            let out_dir = dir.join(layout.id());
+            let results = batch_resize_and_save_parallel(&inputs, &out_dir, &sizes, &options);
+            let events = events.lock().unwrap();
+
+            for (input, result) in &results {
+                let saved = &result.as_ref().unwrap().saved;
+                let written: u64 = saved.iter().map(|path| std::fs::metadata(path).unwrap().len()).sum();
+                let outputs: Vec<(&str, u64)> = events
+                    .iter()
+                    .filter_map(|progress| match progress {
+                        Progress::Output { input: reported, size, bytes } if reported == input => {
+                            Some((size.as_str(), *bytes))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                assert_eq!(outputs.len(), sizes.len(), "{:?}", layout);
+                assert_eq!(outputs.iter().map(|(_, bytes)| bytes).sum::<u64>(), written);
+
+                // Every output of an image comes before it is finished
+                let finished = events
+                    .iter()
+                    .position(|progress| progress == &Progress::Finished { input: input.clone(), bytes: written })
+                    .unwrap();
+                let before = events[..finished]
+                    .iter()
+                    .filter(|progress| matches!(progress, Progress::Output { input: reported, .. } if reported == input))
+                    .count();
+                assert_eq!(before, sizes.len());
+            }
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn errors_tell_bad_inputs_and_sizes_apart() {
+        let dir = std::env::temp_dir().join(format!("jair-errors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let options = ProcessOptions::default();
+        let sizes = parse_size_list("16x16").unwrap();
+
+        let damaged = dir.join("damaged.png");
+        std::fs::write(&damaged, b"\x89PNG\r\n\x1a\nnot really").unwrap();
+        let error = resize_and_save_with_options(&damaged, &dir, &sizes, &options).unwrap_err();
+        assert!(matches!(error, ProcessError::Decode { .. }), "{}", error);
+
+        let unknown = dir.join("notes.txt");
+        std::fs::write(&unknown, b"hello").unwrap();
+        let error = resize_and_save_with_options(&unknown, &dir, &sizes, &options).unwrap_err();
+        assert!(matches!(error, ProcessError::UnsupportedFormat { .. }), "{}", error);
+
+        let empty = [Size::new("empty", 0, 0)];
+        let error = resize_and_save_with_options(&damaged, &dir, &empty, &options).unwrap_err();
+        assert!(matches!(error, ProcessError::InvalidSize { ref size } if size == "empty"), "{}", error);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
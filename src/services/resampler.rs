@@ -0,0 +1,210 @@
+use crate::models::ResizeBackend;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, RgbaImage};
+use std::collections::VecDeque;
+use wide::f32x4;
+
+/// Resamples an image to exact dimensions with a Lanczos3 filter. Crops,
+/// fitting and padding are worked out by `processor` before calling it.
+pub trait Resampler: Send + Sync {
+    fn resize_exact(&self, img: &DynamicImage, width: u32, height: u32) -> DynamicImage;
+}
+
+/// The resampler for `backend`
+pub fn resampler(backend: ResizeBackend) -> &'static dyn Resampler {
+    match backend {
+        ResizeBackend::Reference => &ReferenceResampler,
+        ResizeBackend::Simd => &SimdResampler,
+    }
+}
+
+/// `DynamicImage::resize_exact`, the reference other backends are tested against
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReferenceResampler;
+
+impl Resampler for ReferenceResampler {
+    fn resize_exact(&self, img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+        img.resize_exact(width, height, FilterType::Lanczos3)
+    }
+}
+
+/// Lanczos3 with the four channels of a pixel in one `f32x4`. Source rows
+/// are filtered horizontally as the vertical pass reaches them and dropped
+/// once it has moved past, so memory follows the kernel, not the image.
+/// Only 8-bit images are resampled here, others go to the reference.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimdResampler;
+
+impl Resampler for SimdResampler {
+    fn resize_exact(&self, img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+        if img.dimensions() == (width, height) {
+            return img.clone();
+        }
+
+        match img {
+            DynamicImage::ImageRgba8(rgba) => DynamicImage::ImageRgba8(resize_rgba8(rgba, width, height)),
+            DynamicImage::ImageRgb8(_) => {
+                DynamicImage::ImageRgba8(resize_rgba8(&img.to_rgba8(), width, height)).into_rgb8().into()
+            }
+            DynamicImage::ImageLuma8(_) => {
+                DynamicImage::ImageRgba8(resize_rgba8(&img.to_rgba8(), width, height)).into_luma8().into()
+            }
+            DynamicImage::ImageLumaA8(_) => {
+                DynamicImage::ImageRgba8(resize_rgba8(&img.to_rgba8(), width, height)).into_luma_alpha8().into()
+            }
+            _ => ReferenceResampler.resize_exact(img, width, height),
+        }
+    }
+}
+
+/// Source pixels and their weights for one output pixel
+struct Kernel {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+/// Lanczos3 kernels from `src_len` to `dst_len` pixels, placed and
+/// normalized the way the `image` crate does
+fn kernels(src_len: u32, dst_len: u32) -> Vec<Kernel> {
+    let ratio = src_len as f32 / dst_len as f32;
+    let scale = ratio.max(1.0);
+    let support = 3.0 * scale;
+
+    (0..dst_len)
+        .map(|out| {
+            let centre = (out as f32 + 0.5) * ratio;
+            let left = ((centre - support).floor() as i64).clamp(0, i64::from(src_len) - 1);
+            let right = ((centre + support).ceil() as i64).clamp(left + 1, i64::from(src_len));
+
+            // Kernel positions are measured from the left edge of each pixel
+            let centre = centre - 0.5;
+            let mut weights: Vec<f32> = (left..right).map(|i| lanczos3((i as f32 - centre) / scale)).collect();
+            let sum: f32 = weights.iter().sum();
+            weights.iter_mut().for_each(|weight| *weight /= sum);
+
+            Kernel { start: left as usize, weights }
+        })
+        .collect()
+}
+
+fn lanczos3(x: f32) -> f32 {
+    if x.abs() < 3.0 {
+        sinc(x) * sinc(x / 3.0)
+    } else {
+        0.0
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * std::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
+fn resize_rgba8(src: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    let horizontal = kernels(src.width(), width);
+    let vertical = kernels(src.height(), height);
+    let row_len = src.width() as usize * 4;
+    let mut out = RgbaImage::new(width, height);
+
+    // Horizontally filtered source rows, the first one being `first_row`
+    let mut rows: VecDeque<Vec<f32x4>> = VecDeque::new();
+    let mut first_row = 0;
+    let mut sums = vec![f32x4::ZERO; width as usize];
+
+    for (kernel, out_row) in vertical.iter().zip(out.chunks_exact_mut(width as usize * 4)) {
+        let passed = (kernel.start - first_row).min(rows.len());
+        rows.drain(..passed);
+        first_row = kernel.start.max(first_row);
+        while first_row + rows.len() < kernel.start + kernel.weights.len() {
+            let y = first_row + rows.len();
+            rows.push_back(filter_row(&src.as_raw()[y * row_len..(y + 1) * row_len], &horizontal));
+        }
+
+        sums.fill(f32x4::ZERO);
+        for (row, &weight) in rows.iter().skip(kernel.start - first_row).zip(&kernel.weights) {
+            let weight = f32x4::splat(weight);
+            for (sum, &pixel) in sums.iter_mut().zip(row) {
+                *sum = pixel.mul_add(weight, *sum);
+            }
+        }
+
+        for (pixel, sum) in out_row.chunks_exact_mut(4).zip(&sums) {
+            let channels = sum.round().max(f32x4::ZERO).min(f32x4::splat(255.0)).to_array();
+            for (channel, value) in pixel.iter_mut().zip(channels) {
+                *channel = value as u8;
+            }
+        }
+    }
+
+    out
+}
+
+/// One row of RGBA8 pixels filtered to the width of `kernels`
+fn filter_row(pixels: &[u8], kernels: &[Kernel]) -> Vec<f32x4> {
+    let pixels: Vec<f32x4> = pixels
+        .chunks_exact(4)
+        .map(|p| f32x4::from([f32::from(p[0]), f32::from(p[1]), f32::from(p[2]), f32::from(p[3])]))
+        .collect();
+
+    kernels
+        .iter()
+        .map(|kernel| {
+            pixels[kernel.start..kernel.start + kernel.weights.len()]
+                .iter()
+                .zip(&kernel.weights)
+                .fold(f32x4::ZERO, |sum, (&pixel, &weight)| pixel.mul_add(f32x4::splat(weight), sum))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReferenceResampler, Resampler, SimdResampler};
+    use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+    /// Mean and largest difference between two images of the same dimensions
+    fn difference(a: &DynamicImage, b: &DynamicImage) -> (f64, u8) {
+        let (a, b) = (a.to_rgba8(), b.to_rgba8());
+        let differences: Vec<u8> = a.as_raw().iter().zip(b.as_raw()).map(|(a, b)| a.abs_diff(*b)).collect();
+        let mean = differences.iter().map(|&d| f64::from(d)).sum::<f64>() / differences.len() as f64;
+        (mean, differences.into_iter().max().unwrap_or(0))
+    }
+
+    #[test]
+    fn simd_matches_the_reference() {
+        let rgba = DynamicImage::ImageRgba8(RgbaImage::from_fn(301, 203, |x, y| {
+            Rgba([(x * 3 % 256) as u8, (y * 5 % 256) as u8, ((x ^ y) % 256) as u8, (x % 200 + 55) as u8])
+        }));
+        let images = [
+            rgba.clone(),
+            DynamicImage::ImageRgb8(rgba.to_rgb8()),
+            DynamicImage::ImageLuma8(rgba.to_luma8()),
+            DynamicImage::ImageLumaA8(rgba.to_luma_alpha8()),
+        ];
+
+        for img in &images {
+            for (width, height) in [(64, 64), (17, 150), (600, 90), (1, 1)] {
+                let expected = ReferenceResampler.resize_exact(img, width, height);
+                let actual = SimdResampler.resize_exact(img, width, height);
+                assert_eq!(actual.dimensions(), (width, height));
+                assert_eq!(actual.color(), img.color());
+
+                let (mean, max) = difference(&expected, &actual);
+                assert!(mean < 0.05 && max <= 1, "{:?} {}x{}: mean {} max {}", img.color(), width, height, mean, max);
+            }
+        }
+    }
+
+    #[test]
+    fn other_pixel_types_use_the_reference() {
+        let img = DynamicImage::ImageRgba16(DynamicImage::ImageRgba8(RgbaImage::from_fn(40, 30, |x, y| {
+            Rgba([x as u8 * 6, y as u8 * 8, 0, 255])
+        })).to_rgba16());
+
+        let expected = ReferenceResampler.resize_exact(&img, 10, 10);
+        assert_eq!(SimdResampler.resize_exact(&img, 10, 10), expected);
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use gettextrs::gettext;
+use crate::models::user_preset::{PresetFile, UserPreset};
+use std::path::{Path, PathBuf};
+
+/// `$XDG_CONFIG_HOME/jair/presets`, falling back to `~/.config/jair/presets`
+pub fn presets_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("jair").join("presets"))
+}
+
+/// Presets read from a folder, and the files that could not be
+#[derive(Debug, Default)]
+pub struct LoadedPresets {
+    pub presets: Vec<UserPreset>,
+    /// Each file that was skipped, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Load every preset in the user's presets folder
+pub fn load_all() -> LoadedPresets {
+    match presets_dir() {
+        Some(dir) => load_from_directory(&dir),
+        None => LoadedPresets::default(),
+    }
+}
+
+/// Load `.toml` and `.json` presets sorted by name. Files that fail to parse
+/// are skipped so one bad file doesn't hide the rest, and listed in `skipped`.
+pub fn load_from_directory(dir: &Path) -> LoadedPresets {
+    let mut loaded = LoadedPresets::default();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return loaded;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || !is_preset_file(&path) {
+            continue;
+        }
+
+        match load_file(&path) {
+            Ok(preset) => loaded.presets.push(preset),
+            Err(e) => loaded.skipped.push((path, format!("{:#}", e))),
+        }
+    }
+
+    loaded.presets.sort_by_key(|preset| preset.name.to_lowercase());
+    loaded.skipped.sort();
+    loaded
+}
+
+pub fn load_file(path: &Path) -> Result<UserPreset> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("{} {}", gettext("Could not read:"), path.display()))?;
+
+    let file: PresetFile = match extension(path).as_deref() {
+        Some("json") => serde_json::from_str(&text)?,
+        _ => toml::from_str(&text)?,
+    };
+
+    let id = path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("preset");
+
+    UserPreset::from_file(id, file).map_err(anyhow::Error::msg)
+}
+
+/// Write `preset` to the user's presets folder, see `save_to_directory`
+pub fn save(preset: &UserPreset) -> Result<PathBuf> {
+    let dir = presets_dir().with_context(|| gettext("Could not find the configuration folder"))?;
+    save_to_directory(&dir, preset)
+}
+
+/// Write `preset` as `<id>.toml`, or over `<id>.json` if the preset was
+/// loaded from JSON, so hand-written files keep their format
+pub fn save_to_directory(dir: &Path, preset: &UserPreset) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("{} {}", gettext("Could not create folder:"), dir.display()))?;
+
+    let file = preset.to_file();
+    let json_path = dir.join(format!("{}.json", preset.id));
+    let (path, text) = if json_path.is_file() {
+        (json_path, serde_json::to_string_pretty(&file)?)
+    } else {
+        (dir.join(format!("{}.toml", preset.id)), toml::to_string(&file)?)
+    };
+
+    std::fs::write(&path, text)
+        .with_context(|| format!("{} {}", gettext("Could not save:"), path.display()))?;
+    Ok(path)
+}
+
+pub fn delete(id: &str) -> Result<()> {
+    let dir = presets_dir().with_context(|| gettext("Could not find the configuration folder"))?;
+    delete_from_directory(&dir, id)
+}
+
+pub fn delete_from_directory(dir: &Path, id: &str) -> Result<()> {
+    for extension in ["toml", "json"] {
+        let path = dir.join(format!("{}.{}", id, extension));
+        if path.is_file() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("{} {}", gettext("Could not delete:"), path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// File name for a new preset called `name` that doesn't clash with
+/// existing files, e.g. `Company Banners` becomes `company-banners`
+pub fn new_id(name: &str) -> String {
+    match presets_dir() {
+        Some(dir) => unique_id(&dir, name),
+        None => slug(name),
+    }
+}
+
+fn unique_id(dir: &Path, name: &str) -> String {
+    let base = slug(name);
+    let taken = |id: &str| {
+        ["toml", "json"]
+            .iter()
+            .any(|extension| dir.join(format!("{}.{}", id, extension)).exists())
+    };
+
+    let mut id = base.clone();
+    let mut counter = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    id
+}
+
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "preset".to_string()
+    } else {
+        slug
+    }
+}
+
+fn is_preset_file(path: &Path) -> bool {
+    matches!(extension(path).as_deref(), Some("toml" | "json"))
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{delete_from_directory, load_from_directory, save_to_directory, unique_id};
+    use crate::models::user_preset::UserPreset;
+    use crate::models::{OutputFormat, OutputLayout, Size};
+
+    #[test]
+    fn loads_toml_and_json_and_skips_broken_files() {
+        let dir = std::env::temp_dir().join(format!("jair-presets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(
+            dir.join("banners.toml"),
+            "name = \"Company Banners\"\nlayout = \"folders\"\n\n[[sizes]]\nname = \"hero\"\nwidth = 1920\nheight = 600\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("avatars.json"),
+            r#"{"name": "Avatars", "sizes": [{"name": "avatar", "width": 256, "height": 256}]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.toml"), "name = \"Broken\"\nsizes = []\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a preset").unwrap();
+
+        let loaded = load_from_directory(&dir);
+        let presets = loaded.presets;
+        let names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+        assert_eq!(names, ["Avatars", "Company Banners"]);
+        assert_eq!(loaded.skipped.len(), 1);
+        assert_eq!(loaded.skipped[0].0, dir.join("broken.toml"));
+        assert_eq!(loaded.skipped[0].1, "The preset has no sizes");
+        assert_eq!(presets[1].id, "banners");
+        assert_eq!(presets[1].layout, Some(OutputLayout::SizeFolders));
+        assert_eq!(presets[1].sizes[0].width, 1920);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn saves_and_deletes_presets() {
+        let dir = std::env::temp_dir().join(format!("jair-preset-save-{}", std::process::id()));
+
+        let mut preset = UserPreset {
+            id: unique_id(&dir, "Company Banners!"),
+            name: "Company Banners".to_string(),
+            sizes: vec![Size::new("hero", 1920, 600)],
+            output_format: Some(OutputFormat::WebpLossy),
+            layout: None,
+        };
+        assert_eq!(preset.id, "company-banners");
+
+        let path = save_to_directory(&dir, &preset).unwrap();
+        assert_eq!(path, dir.join("company-banners.toml"));
+        assert_eq!(unique_id(&dir, "company banners"), "company-banners-2");
+
+        // Presets loaded from JSON stay JSON
+        std::fs::write(dir.join("avatars.json"), "{}").unwrap();
+        preset.id = "avatars".to_string();
+        assert_eq!(save_to_directory(&dir, &preset).unwrap(), dir.join("avatars.json"));
+
+        let presets = load_from_directory(&dir).presets;
+        assert_eq!(presets.len(), 2);
+        assert_eq!(presets[0].output_format, Some(OutputFormat::WebpLossy));
+        assert_eq!(presets[0].sizes[0].name, "hero");
+
+        delete_from_directory(&dir, "avatars").unwrap();
+        delete_from_directory(&dir, "company-banners").unwrap();
+        assert!(load_from_directory(&dir).presets.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
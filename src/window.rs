@@ -28,13 +28,14 @@ use adw::subclass::prelude::*;
 use adw::prelude::*;
 use gtk::{gio, glib};
 use glib::prelude::*;
-use gettextrs::gettext;
+use gettextrs::{gettext, ngettext};
 use std::path::PathBuf;
 
 mod imp {
     use super::*;
 
     use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[derive(Debug, Default, gtk::CompositeTemplate)]
@@ -50,30 +51,93 @@ mod imp {
         #[template_child]
         pub preset_combo: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub add_run_preset_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub run_presets_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         pub custom_size_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
         pub custom_width_entry: TemplateChild<gtk::Entry>,
         #[template_child]
         pub custom_height_entry: TemplateChild<gtk::Entry>,
         #[template_child]
+        pub custom_name_entry: TemplateChild<gtk::Entry>,
+        #[template_child]
+        pub custom_size_list_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub add_custom_size_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub custom_sizes_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         pub images_list: TemplateChild<gtk::ListBox>,
         #[template_child]
         pub add_images_btn: TemplateChild<gtk::Button>,
         #[template_child]
+        pub add_folder_btn: TemplateChild<gtk::Button>,
+        #[template_child]
         pub clear_images_btn: TemplateChild<gtk::Button>,
         #[template_child]
-        pub format_switch: TemplateChild<gtk::Switch>,
+        pub format_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub resize_mode_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub upscale_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub padding_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub padding_color_btn: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub layout_combo: TemplateChild<adw::ComboRow>,
         #[template_child]
-        pub format_label: TemplateChild<gtk::Label>,
+        pub resource_name_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub adaptive_background_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub background_color_btn: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub background_image_btn: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub monochrome_switch: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub process_btn: TemplateChild<gtk::Button>,
         #[template_child]
+        pub cancel_btn: TemplateChild<gtk::Button>,
+        #[template_child]
         pub progress_bar: TemplateChild<gtk::ProgressBar>,
         #[template_child]
         pub status_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub details_btn: TemplateChild<gtk::Button>,
 
         // Data
         pub selected_images: RefCell<Vec<PathBuf>>,
+        pub image_rows: RefCell<HashMap<PathBuf, ImageRow>>,
+        pub available_layouts: RefCell<Vec<crate::models::OutputLayout>>,
+        pub adaptive_background: RefCell<crate::models::adaptive_icon::AdaptiveBackground>,
+        pub crop_hints: RefCell<HashMap<PathBuf, crate::models::CropHint>>,
+        /// Subfolder each image was found in by a recursive folder import
+        pub source_folders: RefCell<HashMap<PathBuf, PathBuf>>,
+        pub platforms: RefCell<Vec<crate::models::platform::Platform>>,
+        /// `<platform>/<preset>` ids combined into one run
+        pub run_presets: RefCell<Vec<String>>,
+        pub run_preset_rows: RefCell<Vec<adw::ActionRow>>,
+        pub custom_sizes: RefCell<Vec<crate::models::Size>>,
+        pub custom_size_rows: RefCell<Vec<adw::ActionRow>>,
+        /// Stops the run in progress, if any
+        pub cancel_token: RefCell<Option<crate::services::processor::CancelToken>>,
+        /// Images that failed in the last run
+        pub failures: RefCell<Vec<crate::services::processor::ImageFailure>>,
+    }
+
+    /// Widgets of an image list row that are filled in once its preview loads
+    #[derive(Debug)]
+    pub struct ImageRow {
+        pub row: adw::ActionRow,
+        pub thumbnail: gtk::Image,
+        pub warning: gtk::Image,
+        /// Shown when the image failed in the last run
+        pub error: gtk::Image,
+        pub info: Option<crate::services::image_info::ImageInfo>,
     }
 
     #[glib::object_subclass]
@@ -115,67 +179,369 @@ impl JairWindow {
     }
 
     fn setup_ui(&self) {
-        // Setup platform combo box
-        let platform_list = gtk::StringList::new(&[
-            &gettext("Android"),
-            &gettext("iOS / Apple"),
-            &gettext("Web & Social Media"),
-            &gettext("Custom"),
-        ]);
-        self.imp().platform_combo.set_model(Some(&platform_list));
+        // Setup output format combo box
+        let format_list = gtk::StringList::new(&[]);
+        for format in crate::models::OutputFormat::all() {
+            format_list.append(&gettext(format.name()));
+        }
+        self.imp().format_combo.set_model(Some(&format_list));
+        self.imp().format_combo.set_selected(0);
+
+        // Setup resize mode combo box
+        let mode_list = gtk::StringList::new(&[]);
+        let modes = crate::models::ResizeMode::all();
+        for mode in &modes {
+            mode_list.append(&gettext(mode.name()));
+        }
+        self.imp().resize_mode_combo.set_model(Some(&mode_list));
+        let default_mode = modes
+            .iter()
+            .position(|mode| *mode == crate::models::ResizeMode::default())
+            .unwrap_or(0);
+        self.imp().resize_mode_combo.set_selected(default_mode as u32);
+        self.imp().padding_color_btn.set_rgba(&gtk::gdk::RGBA::TRANSPARENT);
+
+        // Setup upscale policy combo box
+        let upscale_list = gtk::StringList::new(&[]);
+        let policies = crate::models::UpscalePolicy::all();
+        for policy in &policies {
+            upscale_list.append(&gettext(policy.name()));
+        }
+        self.imp().upscale_combo.set_model(Some(&upscale_list));
+        let default_policy = policies
+            .iter()
+            .position(|policy| *policy == crate::models::UpscalePolicy::default())
+            .unwrap_or(0);
+        self.imp().upscale_combo.set_selected(default_policy as u32);
+
+        // Adaptive icon backgrounds default to white
+        self.imp().background_color_btn.set_rgba(&gtk::gdk::RGBA::WHITE);
+
+        // Setup platform combo box, starting with the preset used last time
+        self.update_platform_list();
         self.imp().platform_combo.set_selected(0);
+        self.update_presets_for_platform(0);
+
+        let settings = gio::Settings::new("codes.lsb.jair");
+        self.select_preset_id(&settings.string("last-preset"));
+    }
+
+    fn update_platform_list(&self) {
+        // Presets from ~/.config/jair/presets get their own platform entry
+        let loaded = crate::services::user_presets::load_all();
+        if !loaded.skipped.is_empty() {
+            let names: Vec<String> = loaded
+                .skipped
+                .iter()
+                .map(|(path, error)| format!("{} ({})", path.display(), error))
+                .collect();
+            self.show_status_error(&format!(
+                "{} {}",
+                gettext("Some presets could not be loaded:"),
+                names.join(", ")
+            ));
+        }
+        let platforms = crate::models::platform::platforms(loaded.presets);
+
+        let platform_list = gtk::StringList::new(&[]);
+        for platform in &platforms {
+            platform_list.append(&platform.name);
+        }
+        self.imp().platforms.replace(platforms);
+
+        self.imp().platform_combo.set_model(Some(&platform_list));
+    }
+
+    /// Re-read the presets folder after the preset editor changed it,
+    /// keeping the selected platform and preset where possible
+    pub fn reload_user_presets(&self) {
+        let selected = self.selected_preset_id();
 
-        // Load initial presets for Android
+        self.update_platform_list();
+        self.imp().platform_combo.set_selected(0);
         self.update_presets_for_platform(0);
-        
-        // Initialize format label
-        self.imp().format_label.set_text(&gettext("PNG"));
+
+        // My Presets disappears when the last user preset is deleted
+        if let Some(id) = selected {
+            self.select_preset_id(&id);
+        }
+        self.update_run_presets();
     }
 
-    fn update_presets_for_platform(&self, platform_index: u32) {
+    /// `<platform>/<preset>` id of the selection, or the bare platform id
+    /// when the platform only offers custom sizes
+    fn selected_preset_id(&self) -> Option<String> {
+        let imp = self.imp();
+        let platforms = imp.platforms.borrow();
+        let platform = platforms.get(imp.platform_combo.selected() as usize)?;
+        if platform.is_custom() {
+            return Some(platform.id.to_string());
+        }
+
+        let preset = platform.presets.get(imp.preset_combo.selected() as usize)?;
+        Some(platform.preset_id(preset.as_ref()))
+    }
+
+    /// Select a preset by the ids used on the command line; unknown platforms
+    /// are ignored and unknown presets select the platform's first one
+    fn select_preset_id(&self, id: &str) {
         let imp = self.imp();
+        let (platform_id, preset_id) = id.split_once('/').unwrap_or((id, ""));
+
+        let (platform_index, preset_index) = {
+            let platforms = imp.platforms.borrow();
+            let Some(platform_index) = platforms.iter().position(|platform| platform.id == platform_id) else {
+                return;
+            };
+            let preset_index = platforms[platform_index]
+                .presets
+                .iter()
+                .position(|preset| preset.id() == preset_id);
+            (platform_index as u32, preset_index)
+        };
+
+        imp.platform_combo.set_selected(platform_index);
+        self.update_presets_for_platform(platform_index);
+        if let Some(preset_index) = preset_index {
+            imp.preset_combo.set_selected(preset_index as u32);
+        }
+    }
 
-        // Check if Custom is selected
-        if platform_index == 3 {
-            // Hide preset group and show custom size group
-            imp.preset_group.set_visible(false);
-            imp.custom_size_group.set_visible(true);
+    fn on_add_run_preset_clicked(&self) {
+        let Some(id) = self.selected_preset_id() else {
             return;
+        };
+
+        {
+            let mut run_presets = self.imp().run_presets.borrow_mut();
+            if run_presets.contains(&id) {
+                return;
+            }
+            run_presets.push(id);
         }
+        self.update_run_presets();
+    }
 
-        // Show preset group and hide custom size group for other platforms
-        imp.preset_group.set_visible(true);
-        imp.custom_size_group.set_visible(false);
+    fn remove_run_preset(&self, id: &str) {
+        self.imp().run_presets.borrow_mut().retain(|run_preset| run_preset != id);
+        self.update_run_presets();
+    }
 
-        let string_list = gtk::StringList::new(&[]);
+    /// Rebuild the list of presets in the run, dropping deleted user presets
+    fn update_run_presets(&self) {
+        use crate::models::platform::find_preset;
 
-        match platform_index {
-            0 => {
-                // Android
-                use crate::models::android_sizes::AndroidPreset;
-                for preset in AndroidPreset::all() {
-                    string_list.append(preset.name());
-                }
-            }
-            1 => {
-                // iOS / Apple
-                use crate::models::ios_sizes::IOSPreset;
-                for preset in IOSPreset::all() {
-                    string_list.append(preset.name());
-                }
-            }
-            2 => {
-                // Web & Social Media
-                use crate::models::generic_sizes::GenericPreset;
-                for preset in GenericPreset::all() {
-                    string_list.append(preset.name());
+        let imp = self.imp();
+        for row in imp.run_preset_rows.borrow_mut().drain(..) {
+            imp.run_presets_group.remove(&row);
+        }
+
+        let platforms = imp.platforms.borrow();
+        let mut run_presets = imp.run_presets.borrow_mut();
+        run_presets.retain(|id| find_preset(&platforms, id).is_some());
+
+        for id in run_presets.iter() {
+            let Some((platform_id, _)) = id.split_once('/') else {
+                continue;
+            };
+            let (Some(platform), Some(preset)) = (
+                platforms.iter().find(|platform| platform.id == platform_id),
+                find_preset(&platforms, id),
+            ) else {
+                continue;
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&preset.name()).as_str())
+                .subtitle(glib::markup_escape_text(&platform.name).as_str())
+                .build();
+
+            let remove_btn = gtk::Button::builder()
+                .icon_name("edit-delete-symbolic")
+                .tooltip_text(gettext("Remove from This Run"))
+                .valign(gtk::Align::Center)
+                .build();
+            remove_btn.add_css_class("flat");
+
+            let id = id.clone();
+            remove_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+                window.remove_run_preset(&id);
+            }));
+
+            row.add_suffix(&remove_btn);
+            imp.run_presets_group.add(&row);
+            imp.run_preset_rows.borrow_mut().push(row);
+        }
+
+        imp.run_presets_group.set_visible(!run_presets.is_empty());
+        drop(run_presets);
+        self.update_size_warnings();
+    }
+
+    fn remember_selected_preset(&self) {
+        if let Some(id) = self.selected_preset_id() {
+            let settings = gio::Settings::new("codes.lsb.jair");
+            let _ = settings.set_string("last-preset", &id);
+        }
+    }
+
+    fn update_presets_for_platform(&self, platform_index: u32) {
+        let imp = self.imp();
+
+        self.update_layouts_for_platform(platform_index);
+
+        let string_list = gtk::StringList::new(&[]);
+        let is_custom = match imp.platforms.borrow().get(platform_index as usize) {
+            Some(platform) => {
+                for preset in &platform.presets {
+                    string_list.append(&preset.name());
                 }
+                platform.is_custom()
             }
-            _ => {}
+            None => return,
+        };
+
+        // Custom hides the preset group and shows the custom size group
+        imp.preset_group.set_visible(!is_custom);
+        imp.custom_size_group.set_visible(is_custom);
+        if is_custom {
+            return;
         }
 
         imp.preset_combo.set_model(Some(&string_list));
         imp.preset_combo.set_selected(0);
+        self.apply_preset_defaults();
+    }
+
+    /// Select the format and layout a preset asks for, if any
+    fn apply_preset_defaults(&self) {
+        let imp = self.imp();
+
+        let (output_format, layout) = {
+            let platforms = imp.platforms.borrow();
+            let Some(preset) = platforms
+                .get(imp.platform_combo.selected() as usize)
+                .and_then(|platform| platform.presets.get(imp.preset_combo.selected() as usize))
+            else {
+                return;
+            };
+            (preset.output_format(), preset.layout())
+        };
+
+        if let Some(output_format) = output_format {
+            if let Some(index) = crate::models::OutputFormat::all().iter().position(|f| *f == output_format) {
+                imp.format_combo.set_selected(index as u32);
+            }
+        }
+
+        if let Some(layout) = layout {
+            let index = imp.available_layouts.borrow().iter().position(|l| l.id() == layout.id());
+            if let Some(index) = index {
+                imp.layout_combo.set_selected(index as u32);
+            }
+        }
+    }
+
+    fn update_layouts_for_platform(&self, platform_index: u32) {
+        use crate::models::OutputLayout;
+
+        let imp = self.imp();
+
+        let layouts = imp.platforms.borrow()
+            .get(platform_index as usize)
+            .map(|platform| platform.layouts.clone())
+            .unwrap_or_else(|| vec![OutputLayout::SizeFolders]);
+
+        let string_list = gtk::StringList::new(&[]);
+        for layout in &layouts {
+            string_list.append(&gettext(layout.name()));
+        }
+
+        imp.layout_combo.set_model(Some(&string_list));
+        imp.layout_combo.set_selected(0);
+        imp.layout_combo.set_visible(layouts.len() > 1);
+        imp.available_layouts.replace(layouts);
+        self.update_layout_options();
+    }
+
+    fn update_layout_options(&self) {
+        use crate::models::OutputLayout;
+
+        let imp = self.imp();
+        let is_android_res = matches!(
+            imp.available_layouts.borrow().get(imp.layout_combo.selected() as usize),
+            Some(OutputLayout::AndroidRes { .. })
+        );
+        imp.resource_name_entry.set_visible(is_android_res);
+        imp.adaptive_background_row.set_visible(is_android_res);
+        imp.monochrome_switch.set_visible(is_android_res);
+    }
+
+    fn on_background_color_changed(&self) {
+        use crate::models::adaptive_icon::AdaptiveBackground;
+
+        let imp = self.imp();
+        let rgba = imp.background_color_btn.rgba();
+        imp.adaptive_background.replace(AdaptiveBackground::Color([
+            color_channel(rgba.red()),
+            color_channel(rgba.green()),
+            color_channel(rgba.blue()),
+        ]));
+        imp.adaptive_background_row.set_subtitle(&gettext("Solid color behind the foreground layer"));
+    }
+
+    fn on_background_image_clicked(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Image files")));
+        filter.add_mime_type("image/*");
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Select Background Image"))
+            .modal(true)
+            .filters(&filters)
+            .build();
+
+        dialog.open(
+            Some(self),
+            gio::Cancellable::NONE,
+            glib::clone!(@weak self as window => move |result| {
+                use crate::models::adaptive_icon::AdaptiveBackground;
+
+                if let Some(path) = result.ok().and_then(|file| file.path()) {
+                    let imp = window.imp();
+                    let filename = path.file_name()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("Unknown")
+                        .to_string();
+                    imp.adaptive_background.replace(AdaptiveBackground::Image(path));
+                    imp.adaptive_background_row.set_subtitle(&filename);
+                }
+            }),
+        );
+    }
+
+    fn selected_resize_mode(&self) -> crate::models::ResizeMode {
+        let imp = self.imp();
+        let mode = crate::models::ResizeMode::all()
+            .get(imp.resize_mode_combo.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+
+        match mode {
+            crate::models::ResizeMode::Contain(_) => {
+                let rgba = imp.padding_color_btn.rgba();
+                crate::models::ResizeMode::Contain([
+                    color_channel(rgba.red()),
+                    color_channel(rgba.green()),
+                    color_channel(rgba.blue()),
+                    color_channel(rgba.alpha()),
+                ])
+            }
+            mode => mode,
+        }
     }
 
     fn setup_signals(&self) {
@@ -185,32 +551,84 @@ impl JairWindow {
         imp.platform_combo.connect_selected_notify(glib::clone!(@weak self as window => move |combo| {
             let selected = combo.selected();
             window.update_presets_for_platform(selected);
+            window.remember_selected_preset();
+            window.update_size_warnings();
         }));
 
+        // Presets may select a format and layout
+        imp.preset_combo.connect_selected_notify(glib::clone!(@weak self as window => move |_| {
+            window.apply_preset_defaults();
+            window.remember_selected_preset();
+            window.update_size_warnings();
+        }));
+
+        // Layout combo changed
+        imp.layout_combo.connect_selected_notify(glib::clone!(@weak self as window => move |_| {
+            window.update_layout_options();
+        }));
+
+        // Padding color only applies to the contain mode
+        imp.resize_mode_combo.connect_selected_notify(glib::clone!(@weak self as window => move |_| {
+            let contain = matches!(window.selected_resize_mode(), crate::models::ResizeMode::Contain(_));
+            window.imp().padding_row.set_visible(contain);
+            window.update_size_warnings();
+        }));
+
+        // Adaptive icon background
+        imp.background_color_btn.connect_rgba_notify(glib::clone!(@weak self as window => move |_| {
+            window.on_background_color_changed();
+        }));
+
+        imp.background_image_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.on_background_image_clicked();
+        }));
+
+        // Combine the selected preset with others in one run
+        imp.add_run_preset_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.on_add_run_preset_clicked();
+        }));
+
+        // Custom sizes, one at a time or as a comma separated list
+        imp.add_custom_size_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.on_add_custom_size_clicked();
+        }));
+
+        imp.custom_size_list_entry.connect_apply(glib::clone!(@weak self as window => move |_| {
+            window.on_custom_size_list_applied();
+        }));
+
+        // Drag and drop images and folders
+        self.setup_drop_target();
+
         // Add Images button
         imp.add_images_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
             window.on_add_images_clicked();
         }));
 
+        // Add Folder button
+        imp.add_folder_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.on_add_folder_clicked();
+        }));
+
         // Clear Images button
         imp.clear_images_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
             window.on_clear_images_clicked();
         }));
 
-        // Format switch
-        imp.format_switch.connect_active_notify(glib::clone!(@weak self as window => move |switch| {
-            let label = window.imp().format_label.get();
-            if switch.is_active() {
-                label.set_text(&gettext("PNG"));
-            } else {
-                label.set_text(&gettext("JPEG"));
-            }
-        }));
-
         // Process button
         imp.process_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
             window.on_process_clicked();
         }));
+
+        // Cancel button
+        imp.cancel_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.on_cancel_clicked();
+        }));
+
+        // Details of the images that failed
+        imp.details_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+            window.show_results_dialog();
+        }));
     }
 
     fn on_add_images_clicked(&self) {
@@ -218,7 +636,7 @@ impl JairWindow {
 
         // Create file filter for images
         let filter = gtk::FileFilter::new();
-        filter.set_name(Some("Image files"));
+        filter.set_name(Some(&gettext("Image files")));
         filter.add_mime_type("image/*");
         filter.add_pattern("*.jpg");
         filter.add_pattern("*.jpeg");
@@ -233,7 +651,7 @@ impl JairWindow {
 
         // Use modern FileDialog API (GTK 4.10+)
         let dialog = gtk::FileDialog::builder()
-            .title("Select Images")
+            .title(gettext("Select Images"))
             .modal(true)
             .filters(&filters)
             .build();
@@ -262,23 +680,137 @@ impl JairWindow {
     }
 
     fn add_images_from_files(&self, files: &gio::ListModel) {
-        use crate::services::processor;
+        let paths: Vec<PathBuf> = (0..files.n_items())
+            .filter_map(|i| files.item(i).and_downcast::<gio::File>())
+            .filter_map(|file| file.path())
+            .collect();
 
-        let mut images = self.imp().selected_images.borrow_mut();
+        self.add_image_paths(&paths);
+    }
 
-        for i in 0..files.n_items() {
-            if let Some(file) = files.item(i).and_downcast::<gio::File>() {
-                if let Some(path) = file.path() {
-                    if processor::is_supported_image(&path) && !images.contains(&path) {
-                        images.push(path.clone());
-                        self.add_image_to_list(&path);
+    /// Add image files, expanding directories to the images they contain.
+    /// Folders that can't be read are reported in the status line. Returns
+    /// false when no path was usable and nothing was reported.
+    fn add_image_paths(&self, paths: &[PathBuf]) -> bool {
+        use crate::services::processor;
+
+        let mut found = Vec::new();
+        let mut errors = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                match processor::get_images_from_directory(path) {
+                    Ok(mut images) => {
+                        images.sort();
+                        found.extend(images);
                     }
+                    Err(e) => errors.push(e.to_string()),
                 }
+            } else if processor::is_supported_image(path) {
+                found.push(path.clone());
             }
         }
 
-        // Enable process button if we have images
-        self.imp().process_btn.set_sensitive(!images.is_empty());
+        let mut added = Vec::new();
+        {
+            let mut images = self.imp().selected_images.borrow_mut();
+            for path in &found {
+                if !images.contains(path) {
+                    images.push(path.clone());
+                    self.add_image_to_list(path);
+                    added.push(path.clone());
+                }
+            }
+
+            // Enable process button if we have images
+            self.imp().process_btn.set_sensitive(!images.is_empty());
+        }
+
+        self.load_previews(added);
+
+        if !errors.is_empty() {
+            self.show_status_error(&errors.join("; "));
+        } else if !found.is_empty() {
+            self.imp().status_label.set_visible(false);
+        }
+        !found.is_empty() || !errors.is_empty()
+    }
+
+    fn on_add_folder_clicked(&self) {
+        let dialog = crate::folder_import::JairFolderImport::new();
+        dialog.set_transient_for(Some(self));
+
+        dialog.connect_import(glib::clone!(@weak self as window => move |dialog| {
+            if let Some((folder, options)) = dialog.selection() {
+                window.import_folder(&folder, &options);
+            }
+        }));
+
+        dialog.present();
+    }
+
+    /// Add the images found in `folder`, remembering the subfolder of each so
+    /// the output folder mirrors the source tree
+    fn import_folder(&self, folder: &std::path::Path, options: &crate::services::folder_scan::ScanOptions) {
+        let imp = self.imp();
+
+        let scanned = match crate::services::folder_scan::scan_directory(folder, options) {
+            Ok(scanned) => scanned,
+            Err(e) => {
+                self.show_status_error(&format!("{}: {}", gettext("Could not read the folder"), e));
+                return;
+            }
+        };
+
+        // Subfolders that could not be read are reported once the rest are added
+        let skipped = (!scanned.skipped.is_empty()).then(|| {
+            let folders: Vec<String> = scanned
+                .skipped
+                .iter()
+                .map(|(path, error)| format!("{} ({})", path.display(), error))
+                .collect();
+            format!("{} {}", gettext("Some folders could not be read:"), folders.join(", "))
+        });
+
+        if scanned.images.is_empty() {
+            self.show_status_error(&skipped.unwrap_or_else(|| gettext("No images found in the folder")));
+            return;
+        }
+
+        {
+            let mut source_folders = imp.source_folders.borrow_mut();
+            for image in &scanned.images {
+                if !image.subfolder.as_os_str().is_empty() {
+                    source_folders.insert(image.path.clone(), image.subfolder.clone());
+                }
+            }
+        }
+
+        let paths: Vec<PathBuf> = scanned.images.into_iter().map(|image| image.path).collect();
+        self.add_image_paths(&paths);
+        if let Some(message) = skipped {
+            self.show_status_error(&message);
+        }
+    }
+
+    /// Accept files and folders dropped anywhere on the window
+    fn setup_drop_target(&self) {
+        let drop_target = gtk::DropTarget::new(gtk::gdk::FileList::static_type(), gtk::gdk::DragAction::COPY);
+
+        drop_target.connect_drop(glib::clone!(@weak self as window => @default-return false, move |_, value, _, _| {
+            let Ok(files) = value.get::<gtk::gdk::FileList>() else {
+                return false;
+            };
+
+            let paths: Vec<PathBuf> = files.files().iter().filter_map(|file| file.path()).collect();
+            if window.add_image_paths(&paths) {
+                true
+            } else {
+                window.show_status_error(&gettext("No supported images found in the dropped files"));
+                false
+            }
+        }));
+
+        self.add_controller(drop_target);
     }
 
     fn add_image_to_list(&self, path: &std::path::Path) {
@@ -288,12 +820,49 @@ impl JairWindow {
             .and_then(|s| s.to_str())
             .unwrap_or("Unknown");
 
-        row.set_title(filename);
+        row.set_title(&glib::markup_escape_text(filename));
 
         if let Some(parent) = path.parent() {
-            row.set_subtitle(&parent.display().to_string());
+            row.set_subtitle(&glib::markup_escape_text(&parent.display().to_string()));
         }
 
+        // Thumbnail, filled in by load_previews
+        let thumbnail = gtk::Image::builder()
+            .icon_name("image-x-generic-symbolic")
+            .pixel_size(THUMBNAIL_SIZE)
+            .build();
+        row.add_prefix(&thumbnail);
+
+        // Shown when the source is smaller than a target size
+        let warning = gtk::Image::builder()
+            .icon_name("dialog-warning-symbolic")
+            .visible(false)
+            .build();
+        warning.add_css_class("warning");
+        row.add_suffix(&warning);
+
+        let error = gtk::Image::builder()
+            .icon_name("dialog-error-symbolic")
+            .visible(false)
+            .build();
+        error.add_css_class("error");
+        row.add_suffix(&error);
+
+        // Add crop button
+        let crop_btn = gtk::Button::builder()
+            .icon_name("find-location-symbolic")
+            .tooltip_text(gettext("Crop and Focal Point"))
+            .valign(gtk::Align::Center)
+            .build();
+        crop_btn.add_css_class("flat");
+
+        let crop_path = path.to_path_buf();
+        crop_btn.connect_clicked(glib::clone!(@weak self as window => move |button| {
+            window.on_crop_clicked(&crop_path, button);
+        }));
+
+        row.add_suffix(&crop_btn);
+
         // Add remove button
         let remove_btn = gtk::Button::builder()
             .icon_name("edit-delete-symbolic")
@@ -308,9 +877,150 @@ impl JairWindow {
 
         row.add_suffix(&remove_btn);
         self.imp().images_list.append(&row);
+
+        self.imp().image_rows.borrow_mut().insert(
+            path.to_path_buf(),
+            imp::ImageRow { row, thumbnail, warning, error, info: None },
+        );
+    }
+
+    /// Decode `paths` on a worker thread and show their thumbnails and
+    /// details as they become ready
+    fn load_previews(&self, paths: Vec<PathBuf>) {
+        use crate::services::image_info;
+
+        if paths.is_empty() {
+            return;
+        }
+
+        let (sender, receiver) = async_channel::unbounded();
+
+        std::thread::spawn(move || {
+            for path in paths {
+                // Scale for HiDPI screens
+                let preview = image_info::load_preview(&path, THUMBNAIL_SIZE as u32 * 2)
+                    .map_err(|e| format!("{:#}", e));
+                if sender.send_blocking((path, preview)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        glib::spawn_future_local(glib::clone!(@weak self as window => async move {
+            while let Ok((path, preview)) = receiver.recv().await {
+                window.show_preview(&path, preview);
+            }
+        }));
+    }
+
+    fn show_preview(&self, path: &std::path::Path, preview: Result<crate::services::image_info::Preview, String>) {
+        let mut image_rows = self.imp().image_rows.borrow_mut();
+        // The image may have been removed while it loaded
+        let Some(image_row) = image_rows.get_mut(path) else {
+            return;
+        };
+
+        let preview = match preview {
+            Ok(preview) => preview,
+            Err(message) => {
+                image_row.thumbnail.set_icon_name(Some("image-missing-symbolic"));
+                image_row.warning.set_tooltip_text(Some(&message));
+                image_row.warning.set_visible(true);
+                return;
+            }
+        };
+
+        let (width, height) = preview.thumbnail.dimensions();
+        let texture = gtk::gdk::MemoryTexture::new(
+            width as i32,
+            height as i32,
+            gtk::gdk::MemoryFormat::R8g8b8a8,
+            &glib::Bytes::from_owned(preview.thumbnail.into_raw()),
+            width as usize * 4,
+        );
+        image_row.thumbnail.set_paintable(Some(&texture));
+
+        let info = preview.info;
+        let mut details = vec![format!("{} × {}", info.width, info.height)];
+        details.extend(info.format.clone());
+        details.push(glib::format_size(info.file_size).to_string());
+
+        let mut subtitle = details.join(" · ");
+        if let Some(parent) = path.parent() {
+            subtitle = format!("{}\n{}", subtitle, parent.display());
+        }
+        image_row.row.set_subtitle(&glib::markup_escape_text(&subtitle));
+        image_row.info = Some(info);
+
+        drop(image_rows);
+        self.update_size_warnings();
+    }
+
+    /// Flag images that are smaller than the largest size they will be resized to
+    fn update_size_warnings(&self) {
+        let sizes = self.selected_sizes().map(|(_, sizes)| sizes).unwrap_or_default();
+        let resize_mode = self.selected_resize_mode();
+        let dimension = |value: u32| {
+            if value == 0 {
+                gettext("auto")
+            } else {
+                value.to_string()
+            }
+        };
+
+        let crop_hints = self.imp().crop_hints.borrow();
+        for (path, image_row) in self.imp().image_rows.borrow().iter() {
+            let Some(info) = &image_row.info else {
+                continue;
+            };
+
+            let crop = crop_hints.get(path).copied();
+            match crate::models::largest_upscaled(&sizes, info.width, info.height, resize_mode, crop) {
+                Some(size) => {
+                    image_row.warning.set_tooltip_text(Some(&format!(
+                        "{} {} ({} × {})",
+                        gettext("Smaller than the largest size it is resized to:"),
+                        size.name,
+                        dimension(size.width),
+                        dimension(size.height),
+                    )));
+                    image_row.warning.set_visible(true);
+                }
+                None => image_row.warning.set_visible(false),
+            }
+        }
+    }
+
+    fn on_crop_clicked(&self, path: &std::path::Path, button: &gtk::Button) {
+        let crop_hint = self.imp().crop_hints.borrow().get(path).copied();
+        let editor = crate::crop_editor::JairCropEditor::new(path, crop_hint);
+        editor.set_transient_for(Some(self));
+
+        let path = path.to_path_buf();
+        editor.connect_applied(glib::clone!(@weak self as window, @weak button => move |editor| {
+            let mut crop_hints = window.imp().crop_hints.borrow_mut();
+            match editor.crop_hint() {
+                Some(crop_hint) => {
+                    crop_hints.insert(path.clone(), crop_hint);
+                    button.add_css_class("accent");
+                }
+                None => {
+                    crop_hints.remove(&path);
+                    button.remove_css_class("accent");
+                }
+            }
+            drop(crop_hints);
+            window.update_size_warnings();
+        }));
+
+        editor.present();
     }
 
     fn remove_image(&self, path: &std::path::Path, row: &adw::ActionRow) {
+        self.imp().crop_hints.borrow_mut().remove(path);
+        self.imp().source_folders.borrow_mut().remove(path);
+        self.imp().image_rows.borrow_mut().remove(path);
+
         let mut images = self.imp().selected_images.borrow_mut();
         images.retain(|p| p != path);
 
@@ -320,8 +1030,121 @@ impl JairWindow {
         self.imp().process_btn.set_sensitive(!images.is_empty());
     }
 
+    /// The size typed into the width, height and folder name entries, if any
+    fn pending_custom_size(&self) -> Result<Option<crate::models::Size>, String> {
+        let imp = self.imp();
+        let width_text = imp.custom_width_entry.text();
+        let height_text = imp.custom_height_entry.text();
+        if width_text.trim().is_empty() && height_text.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut size = crate::models::parse_custom_size(&width_text, &height_text)
+            .ok_or_else(|| gettext("Please enter a valid width, height, or both"))?;
+
+        let name = imp.custom_name_entry.text();
+        if !name.trim().is_empty() {
+            if !crate::models::user_preset::is_valid_size_name(&name) {
+                return Err(gettext("Folder names can't contain slashes"));
+            }
+            size.name = name.trim().to_string();
+        }
+
+        Ok(Some(size))
+    }
+
+    fn show_status_error(&self, message: &str) {
+        self.imp().status_label.set_visible(true);
+        self.imp().status_label.set_text(message);
+    }
+
+    fn on_add_custom_size_clicked(&self) {
+        let imp = self.imp();
+
+        match self.pending_custom_size() {
+            Ok(Some(size)) => {
+                imp.custom_sizes.borrow_mut().push(size);
+                imp.custom_width_entry.set_text("");
+                imp.custom_height_entry.set_text("");
+                imp.custom_name_entry.set_text("");
+                imp.status_label.set_visible(false);
+                self.update_custom_sizes();
+            }
+            Ok(None) => self.show_status_error(&gettext("Please enter a valid width, height, or both")),
+            Err(message) => self.show_status_error(&message),
+        }
+    }
+
+    fn on_custom_size_list_applied(&self) {
+        let imp = self.imp();
+
+        match crate::models::parse_size_list(&imp.custom_size_list_entry.text()) {
+            Ok(sizes) => {
+                imp.custom_sizes.borrow_mut().extend(sizes);
+                imp.custom_size_list_entry.set_text("");
+                imp.status_label.set_visible(false);
+                self.update_custom_sizes();
+            }
+            Err(spec) => self.show_status_error(&format!("{} {}", gettext("Invalid size:"), spec)),
+        }
+    }
+
+    fn remove_custom_size(&self, index: usize) {
+        {
+            let mut custom_sizes = self.imp().custom_sizes.borrow_mut();
+            if index < custom_sizes.len() {
+                custom_sizes.remove(index);
+            }
+        }
+        self.update_custom_sizes();
+    }
+
+    fn update_custom_sizes(&self) {
+        let imp = self.imp();
+        for row in imp.custom_size_rows.borrow_mut().drain(..) {
+            imp.custom_sizes_group.remove(&row);
+        }
+
+        let dimension = |value: u32| {
+            if value == 0 {
+                gettext("auto")
+            } else {
+                value.to_string()
+            }
+        };
+
+        let custom_sizes = imp.custom_sizes.borrow();
+        for (index, size) in custom_sizes.iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&size.name).as_str())
+                .subtitle(format!("{} × {}", dimension(size.width), dimension(size.height)))
+                .build();
+
+            let remove_btn = gtk::Button::builder()
+                .icon_name("edit-delete-symbolic")
+                .tooltip_text(gettext("Remove Size"))
+                .valign(gtk::Align::Center)
+                .build();
+            remove_btn.add_css_class("flat");
+
+            remove_btn.connect_clicked(glib::clone!(@weak self as window => move |_| {
+                window.remove_custom_size(index);
+            }));
+
+            row.add_suffix(&remove_btn);
+            imp.custom_sizes_group.add(&row);
+            imp.custom_size_rows.borrow_mut().push(row);
+        }
+
+        imp.custom_sizes_group.set_visible(!custom_sizes.is_empty());
+        self.update_size_warnings();
+    }
+
     fn on_clear_images_clicked(&self) {
         self.imp().selected_images.borrow_mut().clear();
+        self.imp().crop_hints.borrow_mut().clear();
+        self.imp().source_folders.borrow_mut().clear();
+        self.imp().image_rows.borrow_mut().clear();
 
         // Remove all rows from the list
         while let Some(row) = self.imp().images_list.first_child() {
@@ -330,62 +1153,109 @@ impl JairWindow {
 
         self.imp().process_btn.set_sensitive(false);
         self.imp().status_label.set_visible(false);
+        self.imp().details_btn.set_visible(false);
+        self.imp().failures.borrow_mut().clear();
     }
 
-    fn on_process_clicked(&self) {
+    /// Sizes of the presets added to the run, or of the selected preset, plus
+    /// the custom sizes in the list. Custom only uses the custom sizes. Also
+    /// returns whether Custom is selected.
+    fn selected_sizes(&self) -> Option<(bool, Vec<crate::models::Size>)> {
         let imp = self.imp();
-
-        // Get selected platform and preset
-        let platform_idx = imp.platform_combo.selected();
-
-        // Get sizes based on platform
-        let mut sizes = if platform_idx == 3 {
-            // Custom platform - only use custom size
-            Vec::new()
+        let platforms = imp.platforms.borrow();
+        let run_presets = imp.run_presets.borrow();
+        let platform = platforms.get(imp.platform_combo.selected() as usize)?;
+
+        let (is_custom, mut sizes) = if !run_presets.is_empty() {
+            let sizes = run_presets
+                .iter()
+                .filter_map(|id| crate::models::platform::find_preset(&platforms, id))
+                .flat_map(|preset| preset.sizes())
+                .collect();
+            (platform.is_custom(), sizes)
+        } else if platform.is_custom() {
+            (true, Vec::new())
         } else {
-            let preset_idx = imp.preset_combo.selected() as usize;
-            match platform_idx {
-                0 => {
-                    // Android
-                    use crate::models::android_sizes::AndroidPreset;
-                    let preset = AndroidPreset::all()[preset_idx];
-                    preset.get_sizes()
-                }
-                1 => {
-                    // iOS / Apple
-                    use crate::models::ios_sizes::IOSPreset;
-                    let preset = IOSPreset::all()[preset_idx];
-                    preset.get_sizes()
-                }
-                2 => {
-                    // Web & Social Media
-                    use crate::models::generic_sizes::GenericPreset;
-                    let preset = GenericPreset::all()[preset_idx];
-                    preset.get_sizes()
-                }
-                _ => return,
-            }
+            (false, platform.presets.get(imp.preset_combo.selected() as usize)?.sizes())
         };
 
-        // Add custom size if provided (or if Custom platform is selected)
-        let custom_width_text = imp.custom_width_entry.text();
-        let custom_height_text = imp.custom_height_entry.text();
+        sizes.extend(imp.custom_sizes.borrow().iter().cloned());
+        Some((is_custom, sizes))
+    }
 
-        if let Some(size) = parse_custom_size(&custom_width_text, &custom_height_text) {
-            sizes.push(size);
+    fn on_process_clicked(&self) {
+        let imp = self.imp();
+
+        let Some((is_custom, mut sizes)) = self.selected_sizes() else {
+            return;
+        };
+
+        // Include a custom size typed but not added to the list yet
+        match self.pending_custom_size() {
+            Ok(Some(size)) => sizes.push(size),
+            Ok(None) => {}
+            Err(message) if is_custom => {
+                self.show_status_error(&message);
+                return;
+            }
+            Err(_) => {}
         }
+        let sizes = crate::models::merge_sizes(sizes);
 
         // If Custom platform is selected and no valid custom size, show error
-        if platform_idx == 3 && sizes.is_empty() {
-            imp.status_label.set_visible(true);
-            imp.status_label.set_text(&gettext(
-                "Please enter a valid width, height, or both",
-            ));
+        if is_custom && sizes.is_empty() {
+            self.show_status_error(&gettext("Please enter a valid width, height, or both"));
             return;
         }
 
         // Get output format
-        let use_png = imp.format_switch.is_active();
+        let output_format = crate::models::OutputFormat::all()
+            .get(imp.format_combo.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+
+        let resize_mode = self.selected_resize_mode();
+
+        // Get folder layout
+        let mut layout = imp.available_layouts.borrow()
+            .get(imp.layout_combo.selected() as usize)
+            .cloned()
+            .unwrap_or_default();
+
+        if let crate::models::OutputLayout::AndroidRes { resource_name, adaptive } = &mut layout {
+            use crate::models::adaptive_icon::MonochromeLayer;
+
+            *resource_name = imp.resource_name_entry.text().trim().to_string();
+            adaptive.background = imp.adaptive_background.borrow().clone();
+            adaptive.monochrome = imp.monochrome_switch.is_active()
+                .then_some(MonochromeLayer::FromForeground);
+
+            if !crate::models::is_valid_resource_name(resource_name) {
+                imp.status_label.set_visible(true);
+                imp.status_label.set_text(&gettext(
+                    "Android resource names may only contain lowercase letters, digits and underscores",
+                ));
+                return;
+            }
+        }
+
+        let options = crate::services::processor::ProcessOptions {
+            output_format,
+            encoder: crate::preferences::load_encoder_settings(),
+            layout,
+            resize_mode,
+            upscale: crate::models::UpscalePolicy::all()
+                .get(imp.upscale_combo.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            resample: crate::preferences::load_resample_strategy(),
+            resize_backend: crate::preferences::load_resize_backend(),
+            crop_hints: imp.crop_hints.borrow().clone(),
+            source_folders: imp.source_folders.borrow().clone(),
+            cancel: crate::services::processor::CancelToken::default(),
+            threads: crate::preferences::load_thread_count(),
+            progress: crate::services::processor::ProgressHandler::default(),
+        };
 
         // Get selected images
         let images = imp.selected_images.borrow().clone();
@@ -396,7 +1266,7 @@ impl JairWindow {
 
         // Show file chooser for output directory using modern FileDialog API
         let dialog = gtk::FileDialog::builder()
-            .title("Select Output Directory")
+            .title(gettext("Select Output Directory"))
             .modal(true)
             .build();
 
@@ -407,7 +1277,7 @@ impl JairWindow {
                 match result {
                     Ok(file) => {
                         if let Some(out_dir) = file.path() {
-                            window.process_images(images.clone(), out_dir, sizes.clone(), use_png);
+                            window.process_images(images.clone(), out_dir, sizes.clone(), options.clone());
                         }
                     }
                     Err(e) => {
@@ -418,12 +1288,42 @@ impl JairWindow {
         );
     }
 
+    /// Stop the run after the size being written; the image it belongs to
+    /// is discarded
+    fn on_cancel_clicked(&self) {
+        let imp = self.imp();
+        if let Some(token) = imp.cancel_token.borrow().as_ref() {
+            token.cancel();
+        }
+        imp.cancel_btn.set_sensitive(false);
+        imp.status_label.set_text(&gettext("Cancelling…"));
+    }
+
+    /// Show an error icon with the reason on the rows of images that failed
+    fn mark_failures(&self, failures: &[crate::services::processor::ImageFailure]) {
+        for (path, image_row) in self.imp().image_rows.borrow().iter() {
+            match failures.iter().find(|failure| &failure.input == path) {
+                Some(failure) => {
+                    image_row.error.set_tooltip_text(Some(&failure.message));
+                    image_row.error.set_visible(true);
+                }
+                None => image_row.error.set_visible(false),
+            }
+        }
+    }
+
+    fn show_results_dialog(&self) {
+        let dialog = crate::results_dialog::JairResultsDialog::new(&self.imp().failures.borrow());
+        dialog.set_transient_for(Some(self));
+        dialog.present();
+    }
+
     fn process_images(
         &self,
         images: Vec<PathBuf>,
         out_dir: PathBuf,
         sizes: Vec<crate::models::Size>,
-        use_png: bool,
+        mut options: crate::services::processor::ProcessOptions,
     ) {
         use crate::services::processor;
 
@@ -433,108 +1333,157 @@ impl JairWindow {
         imp.progress_bar.set_visible(true);
         imp.progress_bar.set_fraction(0.0);
         imp.status_label.set_visible(true);
-        imp.status_label.set_text("Processing images...");
-        imp.process_btn.set_sensitive(false);
-
-        // Process in a separate thread
+        imp.status_label.set_text(&gettext("Processing images..."));
+        imp.process_btn.set_visible(false);
+        imp.cancel_btn.set_sensitive(true);
+        imp.cancel_btn.set_visible(true);
+        imp.cancel_token.replace(Some(options.cancel.clone()));
+        imp.details_btn.set_visible(false);
+        self.mark_failures(&[]);
+
+        // Process in a separate thread, images and sizes in parallel
         let (sender, receiver) = async_channel::unbounded();
+        let progress_sender = sender.clone();
+        options.progress = processor::ProgressHandler::new(move |progress| {
+            let _ = progress_sender.send_blocking(ProcessMessage::Progress(progress));
+        });
+        let total = images.len();
+        let sizes_per_image = sizes.len();
 
         std::thread::spawn(move || {
-            let total = images.len();
-            let mut processed = 0;
             let mut successful = 0;
             let mut failed = 0;
+            let mut notes = Vec::new();
+            let mut failures = Vec::new();
+            let mut cancelled = false;
 
-            for image in &images {
-                match processor::resize_and_save(image, &out_dir, &sizes, use_png) {
-                    Ok(_) => successful += 1,
-                    Err(_) => failed += 1,
+            for (image, result) in processor::batch_resize_and_save_parallel(&images, &out_dir, &sizes, &options) {
+                match result {
+                    Ok(report) => {
+                        successful += 1;
+                        notes.extend(upscale_notes(&image, &report));
+                    }
+                    Err(e) if matches!(e.root(), processor::ProcessError::Cancelled) => cancelled = true,
+                    Err(e) => {
+                        failed += 1;
+                        failures.push(processor::ImageFailure::new(&image, &e));
+                    }
                 }
-
-                processed += 1;
-                let progress = processed as f64 / total as f64;
-                let _ = sender.send_blocking((progress, processed, total, successful, failed, false));
             }
 
             // Send completion signal
-            let _ = sender.send_blocking((1.0, processed, total, successful, failed, true));
+            let _ = sender.send_blocking(ProcessMessage::Done { successful, failed, total, notes, failures, cancelled });
         });
 
         // Update UI from main thread
         glib::spawn_future_local(glib::clone!(@weak self as window => async move {
-            while let Ok((progress, processed, total, successful, failed, done)) = receiver.recv().await {
+            // Outputs written so far per image; an image that finishes counts
+            // all its sizes, including skipped or failed ones
+            let mut written: std::collections::HashMap<PathBuf, usize> = Default::default();
+            let mut outputs_done = 0;
+            let mut images_done = 0;
+            let mut bytes_written = 0;
+            let outputs_total = (total * sizes_per_image).max(1);
+
+            while let Ok(message) = receiver.recv().await {
                 let imp = window.imp();
-                imp.progress_bar.set_fraction(progress);
 
-                if done {
-                    imp.progress_bar.set_visible(false);
-                    imp.process_btn.set_sensitive(true);
+                match message {
+                    ProcessMessage::Progress(processor::Progress::Output { input, size, .. }) => {
+                        *written.entry(input.clone()).or_default() += 1;
+                        outputs_done += 1;
 
-                    let status = format!(
-                        "Completed: {} successful, {} failed out of {} images",
-                        successful, failed, total
-                    );
-                    imp.status_label.set_text(&status);
-                    break;
-                } else {
-                    let status = format!("Processing {} of {} images...", processed, total);
-                    imp.status_label.set_text(&status);
+                        let name = input.file_name().unwrap_or_default().to_string_lossy();
+                        imp.progress_bar.set_text(Some(&format!("{} · {}", name, size)));
+                        imp.progress_bar.set_show_text(true);
+                    }
+                    ProcessMessage::Progress(processor::Progress::Finished { input, bytes }) => {
+                        let count = written.get(&input).copied().unwrap_or(0);
+                        outputs_done += sizes_per_image.saturating_sub(count);
+                        images_done += 1;
+                        // Only count what was moved out of staging
+                        bytes_written += bytes;
+                    }
+                    ProcessMessage::Done { successful, failed, total, notes, failures, cancelled } => {
+                        imp.progress_bar.set_show_text(false);
+                        imp.progress_bar.set_visible(false);
+                        imp.cancel_btn.set_visible(false);
+                        imp.process_btn.set_visible(true);
+                        imp.cancel_token.replace(None);
+
+                        let mut status = if cancelled {
+                            ngettext(
+                                "Cancelled: {successful} successful, {failed} failed, {skipped} not processed out of {total} image",
+                                "Cancelled: {successful} successful, {failed} failed, {skipped} not processed out of {total} images",
+                                total as u32,
+                            )
+                            .replace("{skipped}", &(total - successful - failed).to_string())
+                        } else {
+                            ngettext(
+                                "Completed: {successful} successful, {failed} failed out of {total} image",
+                                "Completed: {successful} successful, {failed} failed out of {total} images",
+                                total as u32,
+                            )
+                        }
+                        .replace("{successful}", &successful.to_string())
+                        .replace("{failed}", &failed.to_string())
+                        .replace("{total}", &total.to_string());
+                        for note in notes {
+                            status.push('\n');
+                            status.push_str(&note);
+                        }
+                        imp.status_label.set_text(&status);
+
+                        imp.details_btn.set_visible(!failures.is_empty());
+                        window.mark_failures(&failures);
+                        imp.failures.replace(failures);
+                        break;
+                    }
                 }
+
+                imp.progress_bar.set_fraction(outputs_done as f64 / outputs_total as f64);
+                let status =
+                    gettext("Processing images... {done} of {total} done, {bytes} written")
+                        .replace("{done}", &images_done.to_string())
+                        .replace("{total}", &total.to_string())
+                        .replace("{bytes}", &glib::format_size(bytes_written));
+                imp.status_label.set_text(&status);
             }
         }));
     }
 }
 
-/// A zero dimension is resolved from the source image's aspect ratio by the processor.
-fn parse_custom_size(width_text: &str, height_text: &str) -> Option<crate::models::Size> {
-    let parse_dimension = |text: &str| {
-        if text.trim().is_empty() {
-            Some(0)
-        } else {
-            text.trim().parse::<u32>().ok().filter(|value| *value > 0)
-        }
-    };
+/// Sent from the processing threads to the window
+enum ProcessMessage {
+    Progress(crate::services::processor::Progress),
+    Done {
+        successful: usize,
+        failed: usize,
+        total: usize,
+        notes: Vec<String>,
+        failures: Vec<crate::services::processor::ImageFailure>,
+        cancelled: bool,
+    },
+}
 
-    let width = parse_dimension(width_text)?;
-    let height = parse_dimension(height_text)?;
+/// Lines for the completion status about sizes larger than `image`
+fn upscale_notes(image: &std::path::Path, report: &crate::services::processor::ImageReport) -> Vec<String> {
+    let name = image.file_name().unwrap_or_default().to_string_lossy();
+    let mut notes = Vec::new();
 
-    if width == 0 && height == 0 {
-        return None;
+    if !report.upscaled.is_empty() {
+        notes.push(format!("{}: {} {}", name, gettext("Enlarged beyond the source:"), report.upscaled.join(", ")));
     }
-
-    let name = match (width, height) {
-        (width, 0) => format!("Custom_{}w", width),
-        (0, height) => format!("Custom_{}h", height),
-        (width, height) => format!("Custom_{}x{}", width, height),
-    };
-
-    Some(crate::models::Size {
-        width,
-        height,
-        name,
-    })
+    if !report.skipped.is_empty() {
+        notes.push(format!("{}: {} {}", name, gettext("Skipped, larger than the source:"), report.skipped.join(", ")));
+    }
+    notes
 }
 
-#[cfg(test)]
-mod tests {
-    use super::parse_custom_size;
+/// Side of the thumbnails in the image list, in logical pixels
+const THUMBNAIL_SIZE: i32 = 48;
 
-    #[test]
-    fn accepts_either_or_both_custom_dimensions() {
-        let width_only = parse_custom_size("1920", "").unwrap();
-        assert_eq!((width_only.width, width_only.height), (1920, 0));
-
-        let height_only = parse_custom_size("", "1080").unwrap();
-        assert_eq!((height_only.width, height_only.height), (0, 1080));
-
-        let both = parse_custom_size("1920", "1080").unwrap();
-        assert_eq!((both.width, both.height), (1920, 1080));
-    }
-
-    #[test]
-    fn rejects_empty_zero_or_invalid_dimensions() {
-        assert!(parse_custom_size("", "").is_none());
-        assert!(parse_custom_size("0", "1080").is_none());
-        assert!(parse_custom_size("wide", "1080").is_none());
-    }
+/// Convert a `gdk::RGBA` channel to 8 bits
+fn color_channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
 }